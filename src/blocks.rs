@@ -0,0 +1,591 @@
+//! Lossless block-level view of a GIF file.
+//!
+//! [`parse`] walks the raw bytes of a GIF and returns a [`GifFile`] describing
+//! every structural element in file order: header, logical screen descriptor,
+//! palettes, extensions, image descriptors and their LZW sub-block chains.
+//! Every element carries the byte offset and length it occupies, so callers can
+//! inspect or rewrite individual blocks while copying everything else verbatim.
+
+use serde::Serialize;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::gifmeta_structs::GifError;
+
+/// Extension Introducer (`!`).
+pub const EXTENSION_INTRODUCER: u8 = 0x21;
+/// Image Separator (`,`).
+pub const IMAGE_SEPARATOR: u8 = 0x2C;
+/// Trailer (`;`).
+pub const TRAILER: u8 = 0x3B;
+
+/// Plain Text Extension label.
+pub const LABEL_PLAIN_TEXT: u8 = 0x01;
+/// Graphic Control Extension label.
+pub const LABEL_GRAPHIC_CONTROL: u8 = 0xF9;
+/// Comment Extension label.
+pub const LABEL_COMMENT: u8 = 0xFE;
+/// Application Extension label.
+pub const LABEL_APPLICATION: u8 = 0xFF;
+
+/// A byte range inside the parsed file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn new(offset: usize, len: usize) -> Self {
+        Span { offset, len }
+    }
+
+    /// Offset of the first byte after this span.
+    pub fn end(&self) -> usize {
+        self.offset + self.len
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.end()
+    }
+}
+
+/// The 6-byte `GIF87a` / `GIF89a` signature.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Header {
+    pub span: Span,
+    /// `"87a"` or `"89a"`.
+    pub version: String,
+}
+
+/// The 7-byte Logical Screen Descriptor.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogicalScreen {
+    pub span: Span,
+    pub width: u16,
+    pub height: u16,
+    pub has_global_palette: bool,
+    pub color_resolution: u8,
+    pub sorted: bool,
+    /// Number of RGB entries declared by the size field (2..=256).
+    pub global_palette_entries: usize,
+    pub background_index: u8,
+    pub pixel_aspect_ratio: u8,
+}
+
+/// A global or local color table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Palette {
+    pub span: Span,
+    /// Number of RGB entries.
+    pub entries: usize,
+}
+
+/// A chain of data sub-blocks, including its zero-length terminator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubBlocks {
+    /// The whole chain, from the first size byte through the terminator.
+    pub span: Span,
+    /// Payload of each sub-block, excluding its size byte.
+    pub chunks: Vec<Span>,
+}
+
+impl SubBlocks {
+    /// Total payload length across all sub-blocks.
+    pub fn data_len(&self) -> usize {
+        self.chunks.iter().map(|c| c.len).sum()
+    }
+
+    /// Concatenates the payload of every sub-block.
+    pub fn collect(&self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.data_len());
+        for chunk in &self.chunks {
+            out.extend_from_slice(&data[chunk.range()]);
+        }
+        out
+    }
+}
+
+/// Graphic Control Extension (`0x21 0xF9`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphicControl {
+    pub span: Span,
+    pub disposal: u8,
+    pub user_input: bool,
+    pub transparent_index: Option<u8>,
+    pub delay_cs: u16,
+    /// Offset of the little-endian delay field.
+    pub delay_offset: usize,
+}
+
+/// Comment Extension (`0x21 0xFE`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Comment {
+    pub span: Span,
+    pub data: SubBlocks,
+}
+
+/// Application Extension (`0x21 0xFF`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Application {
+    pub span: Span,
+    /// The application identifier and authentication code, e.g. `"NETSCAPE2.0"`.
+    pub identifier: String,
+    /// The fixed-size block holding the identifier (normally 11 bytes).
+    pub header: Span,
+    pub data: SubBlocks,
+}
+
+/// Plain Text Extension (`0x21 0x01`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlainText {
+    pub span: Span,
+    pub left: u16,
+    pub top: u16,
+    pub width: u16,
+    pub height: u16,
+    pub cell_width: u8,
+    pub cell_height: u8,
+    pub foreground_index: u8,
+    pub background_index: u8,
+    pub data: SubBlocks,
+}
+
+/// Any extension with a label gifmeta does not interpret.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnknownExtension {
+    pub span: Span,
+    pub label: u8,
+    pub data: SubBlocks,
+}
+
+/// Image Descriptor with its optional local palette and LZW data.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Image {
+    pub span: Span,
+    /// The 10-byte Image Descriptor.
+    pub descriptor: Span,
+    pub left: u16,
+    pub top: u16,
+    pub width: u16,
+    pub height: u16,
+    pub interlaced: bool,
+    pub sorted: bool,
+    pub local_palette: Option<Palette>,
+    pub lzw_min_code_size: u8,
+    /// Offset of the LZW minimum code size byte.
+    pub lzw_offset: usize,
+    pub data: SubBlocks,
+}
+
+/// A block following the logical screen, in file order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Block {
+    GraphicControl(GraphicControl),
+    Comment(Comment),
+    Application(Application),
+    PlainText(PlainText),
+    Extension(UnknownExtension),
+    Image(Image),
+}
+
+impl Block {
+    /// Bytes occupied by the whole block.
+    pub fn span(&self) -> Span {
+        match self {
+            Block::GraphicControl(b) => b.span,
+            Block::Comment(b) => b.span,
+            Block::Application(b) => b.span,
+            Block::PlainText(b) => b.span,
+            Block::Extension(b) => b.span,
+            Block::Image(b) => b.span,
+        }
+    }
+
+    /// Machine-readable block type, matching the serialized `kind` tag.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Block::GraphicControl(_) => "graphic_control",
+            Block::Comment(_) => "comment",
+            Block::Application(_) => "application",
+            Block::PlainText(_) => "plain_text",
+            Block::Extension(_) => "extension",
+            Block::Image(_) => "image",
+        }
+    }
+
+    /// Short human-readable name of the block type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Block::GraphicControl(_) => "Graphic Control Extension",
+            Block::Comment(_) => "Comment Extension",
+            Block::Application(_) => "Application Extension",
+            Block::PlainText(_) => "Plain Text Extension",
+            Block::Extension(_) => "Unknown Extension",
+            Block::Image(_) => "Image",
+        }
+    }
+}
+
+/// A fully parsed GIF file together with its raw bytes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GifFile {
+    #[serde(skip)]
+    pub data: Vec<u8>,
+    pub header: Header,
+    pub screen: LogicalScreen,
+    pub global_palette: Option<Palette>,
+    pub blocks: Vec<Block>,
+    /// Offset of the `0x3B` trailer byte.
+    pub trailer: Option<usize>,
+    /// Any bytes found after the trailer.
+    pub trailing: Option<Span>,
+}
+
+impl GifFile {
+    /// Raw bytes of a span.
+    pub fn bytes(&self, span: Span) -> &[u8] {
+        &self.data[span.range()]
+    }
+
+    /// Iterates over image blocks in file order.
+    pub fn images(&self) -> impl Iterator<Item = &Image> {
+        self.blocks.iter().filter_map(|b| match b {
+            Block::Image(img) => Some(img),
+            _ => None,
+        })
+    }
+
+    /// Iterates over application extensions in file order.
+    pub fn applications(&self) -> impl Iterator<Item = &Application> {
+        self.blocks.iter().filter_map(|b| match b {
+            Block::Application(app) => Some(app),
+            _ => None,
+        })
+    }
+
+    /// Pairs every image with the Graphic Control Extension that precedes it, if any.
+    ///
+    /// A Plain Text Extension in between does not use the GCE up: decoders
+    /// still apply it to the image that follows.
+    pub fn frames(&self) -> Vec<(Option<&GraphicControl>, &Image)> {
+        let mut frames = Vec::new();
+        let mut pending = None;
+        for block in &self.blocks {
+            match block {
+                Block::GraphicControl(gce) => pending = Some(gce),
+                Block::Image(img) => frames.push((pending.take(), img)),
+                _ => {}
+            }
+        }
+        frames
+    }
+}
+
+/// Reads and parses a GIF file from disk.
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<GifFile, GifError> {
+    let path = path.as_ref();
+    let data = fs::read(path).map_err(|e| GifError::io(path, e))?;
+    parse(data)
+}
+
+/// Parses raw GIF bytes into a [`GifFile`].
+///
+/// # Example
+/// ```
+/// let gif = gifmeta::blocks::read_file("tests/testdata/loop/2frame-soft.gif").unwrap();
+/// assert_eq!(gif.header.version, "89a");
+/// assert_eq!(gif.images().count(), 2);
+/// ```
+pub fn parse(data: Vec<u8>) -> Result<GifFile, GifError> {
+    match parse_partial(data)? {
+        (gif, None) => Ok(gif),
+        (_, Some(error)) => Err(error),
+    }
+}
+
+/// Parses as much of a damaged GIF as possible.
+///
+/// A broken header, logical screen or global palette is still an error, but a
+/// block that is truncated or malformed ends the walk instead: the returned
+/// [`GifFile`] holds every complete block before it (and no trailer), together
+/// with the error that stopped the walk.
+pub fn parse_partial(data: Vec<u8>) -> Result<(GifFile, Option<GifError>), GifError> {
+    let mut cursor = Cursor {
+        data: &data,
+        pos: 0,
+    };
+
+    let signature = cursor.take(6, "header")?;
+    if &signature[..3] != b"GIF" {
+        return Err(GifError::decode(
+            0,
+            "Not a GIF file: missing 'GIF' signature",
+        ));
+    }
+    let header = Header {
+        span: Span::new(0, 6),
+        version: String::from_utf8_lossy(&signature[3..]).into_owned(),
+    };
+
+    let screen = parse_logical_screen(&mut cursor)?;
+    let global_palette = if screen.has_global_palette {
+        Some(parse_palette(&mut cursor, screen.global_palette_entries)?)
+    } else {
+        None
+    };
+
+    let mut blocks = Vec::new();
+    let mut trailer = None;
+    let mut error = None;
+    while cursor.pos < data.len() {
+        let start = cursor.pos;
+        let block = match cursor.u8("block introducer")? {
+            EXTENSION_INTRODUCER => parse_extension(&mut cursor, start),
+            IMAGE_SEPARATOR => parse_image(&mut cursor, start).map(Block::Image),
+            TRAILER => {
+                trailer = Some(start);
+                break;
+            }
+            other => Err(GifError::decode(
+                start,
+                format!(
+                    "Unexpected byte 0x{:02X} (expected extension, image or trailer)",
+                    other
+                ),
+            )),
+        };
+        match block {
+            Ok(block) => blocks.push(block),
+            Err(e) => {
+                error = Some(e);
+                break;
+            }
+        }
+    }
+
+    let trailing = match trailer {
+        Some(pos) if pos + 1 < data.len() => Some(Span::new(pos + 1, data.len() - pos - 1)),
+        _ => None,
+    };
+
+    let gif = GifFile {
+        data,
+        header,
+        screen,
+        global_palette,
+        blocks,
+        trailer,
+        trailing,
+    };
+    Ok((gif, error))
+}
+
+fn parse_logical_screen(cursor: &mut Cursor) -> Result<LogicalScreen, GifError> {
+    let offset = cursor.pos;
+    let width = cursor.u16("logical screen width")?;
+    let height = cursor.u16("logical screen height")?;
+    let packed = cursor.u8("logical screen flags")?;
+    let background_index = cursor.u8("background color index")?;
+    let pixel_aspect_ratio = cursor.u8("pixel aspect ratio")?;
+    Ok(LogicalScreen {
+        span: Span::new(offset, 7),
+        width,
+        height,
+        has_global_palette: packed & 0b1000_0000 != 0,
+        color_resolution: ((packed >> 4) & 0b0111) + 1,
+        sorted: packed & 0b0000_1000 != 0,
+        global_palette_entries: 2 << (packed & 0b0111),
+        background_index,
+        pixel_aspect_ratio,
+    })
+}
+
+fn parse_palette(cursor: &mut Cursor, entries: usize) -> Result<Palette, GifError> {
+    let offset = cursor.pos;
+    cursor.take(entries * 3, "color table")?;
+    Ok(Palette {
+        span: Span::new(offset, entries * 3),
+        entries,
+    })
+}
+
+fn parse_sub_blocks(cursor: &mut Cursor) -> Result<SubBlocks, GifError> {
+    let offset = cursor.pos;
+    let mut chunks = Vec::new();
+    loop {
+        let size = cursor.u8("sub-block size")? as usize;
+        if size == 0 {
+            break;
+        }
+        let chunk_offset = cursor.pos;
+        cursor.take(size, "sub-block data")?;
+        chunks.push(Span::new(chunk_offset, size));
+    }
+    Ok(SubBlocks {
+        span: Span::new(offset, cursor.pos - offset),
+        chunks,
+    })
+}
+
+fn parse_extension(cursor: &mut Cursor, start: usize) -> Result<Block, GifError> {
+    let label = cursor.u8("extension label")?;
+    match label {
+        LABEL_GRAPHIC_CONTROL => {
+            let size = cursor.u8("graphic control block size")?;
+            if size != 4 {
+                return Err(GifError::decode(
+                    start,
+                    format!(
+                        "Graphic Control Extension has block size {} (expected 4)",
+                        size
+                    ),
+                ));
+            }
+            let packed = cursor.u8("graphic control flags")?;
+            let delay_offset = cursor.pos;
+            let delay_cs = cursor.u16("frame delay")?;
+            let transparent = cursor.u8("transparent color index")?;
+            let terminator = cursor.u8("block terminator")?;
+            if terminator != 0 {
+                return Err(GifError::decode(
+                    start,
+                    "Graphic Control Extension is missing its block terminator",
+                ));
+            }
+            Ok(Block::GraphicControl(GraphicControl {
+                span: Span::new(start, cursor.pos - start),
+                disposal: (packed >> 2) & 0b0111,
+                user_input: packed & 0b0000_0010 != 0,
+                transparent_index: (packed & 0b0000_0001 != 0).then_some(transparent),
+                delay_cs,
+                delay_offset,
+            }))
+        }
+        LABEL_COMMENT => {
+            let data = parse_sub_blocks(cursor)?;
+            Ok(Block::Comment(Comment {
+                span: Span::new(start, cursor.pos - start),
+                data,
+            }))
+        }
+        LABEL_APPLICATION => {
+            let size = cursor.u8("application block size")? as usize;
+            let header_offset = cursor.pos;
+            let identifier = cursor.take(size, "application identifier")?;
+            let identifier = String::from_utf8_lossy(identifier).into_owned();
+            let data = parse_sub_blocks(cursor)?;
+            Ok(Block::Application(Application {
+                span: Span::new(start, cursor.pos - start),
+                identifier,
+                header: Span::new(header_offset, size),
+                data,
+            }))
+        }
+        LABEL_PLAIN_TEXT => {
+            let size = cursor.u8("plain text block size")?;
+            if size != 12 {
+                return Err(GifError::decode(
+                    start,
+                    format!("Plain Text Extension has block size {} (expected 12)", size),
+                ));
+            }
+            let left = cursor.u16("text grid left")?;
+            let top = cursor.u16("text grid top")?;
+            let width = cursor.u16("text grid width")?;
+            let height = cursor.u16("text grid height")?;
+            let cell_width = cursor.u8("character cell width")?;
+            let cell_height = cursor.u8("character cell height")?;
+            let foreground_index = cursor.u8("text foreground color")?;
+            let background_index = cursor.u8("text background color")?;
+            let data = parse_sub_blocks(cursor)?;
+            Ok(Block::PlainText(PlainText {
+                span: Span::new(start, cursor.pos - start),
+                left,
+                top,
+                width,
+                height,
+                cell_width,
+                cell_height,
+                foreground_index,
+                background_index,
+                data,
+            }))
+        }
+        _ => {
+            let data = parse_sub_blocks(cursor)?;
+            Ok(Block::Extension(UnknownExtension {
+                span: Span::new(start, cursor.pos - start),
+                label,
+                data,
+            }))
+        }
+    }
+}
+
+fn parse_image(cursor: &mut Cursor, start: usize) -> Result<Image, GifError> {
+    let left = cursor.u16("image left")?;
+    let top = cursor.u16("image top")?;
+    let width = cursor.u16("image width")?;
+    let height = cursor.u16("image height")?;
+    let packed = cursor.u8("image flags")?;
+    let local_palette = if packed & 0b1000_0000 != 0 {
+        Some(parse_palette(cursor, 2 << (packed & 0b0111))?)
+    } else {
+        None
+    };
+    let lzw_offset = cursor.pos;
+    let lzw_min_code_size = cursor.u8("LZW minimum code size")?;
+    let data = parse_sub_blocks(cursor)?;
+    Ok(Image {
+        span: Span::new(start, cursor.pos - start),
+        descriptor: Span::new(start, 10),
+        left,
+        top,
+        width,
+        height,
+        interlaced: packed & 0b0100_0000 != 0,
+        sorted: packed & 0b0010_0000 != 0,
+        local_palette,
+        lzw_min_code_size,
+        lzw_offset,
+        data,
+    })
+}
+
+/// Bounds-checked reader over the input bytes.
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize, what: &str) -> Result<&'a [u8], GifError> {
+        let end = self.pos + len;
+        if end > self.data.len() {
+            return Err(GifError::decode(
+                self.pos,
+                format!(
+                    "Unexpected end of file reading {} (needed {} bytes, {} available)",
+                    what,
+                    len,
+                    self.data.len() - self.pos
+                ),
+            ));
+        }
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self, what: &str) -> Result<u8, GifError> {
+        Ok(self.take(1, what)?[0])
+    }
+
+    fn u16(&mut self, what: &str) -> Result<u16, GifError> {
+        let bytes = self.take(2, what)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+}
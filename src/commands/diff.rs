@@ -0,0 +1,253 @@
+// src/commands/diff.rs
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::ControlFlow;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::blocks::{self, Block, GifFile, Palette};
+use crate::commands::info;
+use crate::gifmeta_structs::{
+    DiffOptions, Difference, FrameMeta, GifDiff, GifError, GifMetadata, PreviewOptions,
+};
+use crate::utils::extract_frame_as_png::render_frames;
+use crate::utils::loop_count;
+
+/// Shows a diff value without JSON quoting for plain strings.
+pub fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "none".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Collects differences in the order they are found.
+#[derive(Default)]
+struct Differences(Vec<Difference>);
+
+impl Differences {
+    fn push(&mut self, path: impl Into<String>, left: Value, right: Value, note: Option<String>) {
+        self.0.push(Difference {
+            path: path.into(),
+            left,
+            right,
+            note,
+        });
+    }
+
+    /// Records `path` if the two values differ.
+    fn field<T: Serialize + PartialEq>(&mut self, path: impl Into<String>, left: T, right: T) {
+        if left != right {
+            self.push(path, json!(left), json!(right), None);
+        }
+    }
+}
+
+/// Compares two GIFs: dimensions, loop settings, per-frame timing, disposal
+/// and geometry, palettes, comments and extensions, and optionally the
+/// rendered pixels of every frame.
+///
+/// Frames are compared by index; frames only one file has are covered by
+/// the `frame_count` difference.
+pub fn diff_data(
+    left: Vec<u8>,
+    right: Vec<u8>,
+    options: &DiffOptions,
+) -> Result<GifDiff, GifError> {
+    let (left_meta, right_meta) = (
+        info::metadata_from_bytes(left.clone(), true)?,
+        info::metadata_from_bytes(right.clone(), true)?,
+    );
+    let (left_gif, right_gif) = (blocks::parse(left)?, blocks::parse(right)?);
+
+    let mut diff = Differences::default();
+    diff_metadata(&mut diff, &left_meta, &right_meta);
+    diff.field(
+        "version",
+        &left_gif.header.version,
+        &right_gif.header.version,
+    );
+    diff.field(
+        "background_index",
+        left_gif.screen.background_index,
+        right_gif.screen.background_index,
+    );
+    diff_palette(
+        &mut diff,
+        "global_palette",
+        palette_bytes(&left_gif, left_gif.global_palette.as_ref()),
+        palette_bytes(&right_gif, right_gif.global_palette.as_ref()),
+    );
+
+    let left_images = left_gif.images().collect::<Vec<_>>();
+    let right_images = right_gif.images().collect::<Vec<_>>();
+    for (index, (l, r)) in left_meta.frames.iter().zip(&right_meta.frames).enumerate() {
+        diff_frame(&mut diff, index, l, r);
+        if let (Some(l), Some(r)) = (left_images.get(index), right_images.get(index)) {
+            diff_palette(
+                &mut diff,
+                format!("frames[{}].local_palette", index),
+                palette_bytes(&left_gif, l.local_palette.as_ref()),
+                palette_bytes(&right_gif, r.local_palette.as_ref()),
+            );
+        }
+    }
+
+    let comments = |meta: &GifMetadata| -> Vec<String> {
+        meta.comments.iter().map(|c| c.text.clone()).collect()
+    };
+    diff.field("comments", comments(&left_meta), comments(&right_meta));
+    diff_extensions(&mut diff, &left_gif, &right_gif);
+
+    if options.compare_pixels {
+        let (left_hashes, right_hashes) = (frame_hashes(&left_gif)?, frame_hashes(&right_gif)?);
+        for (index, (l, r)) in left_hashes.iter().zip(&right_hashes).enumerate() {
+            if l != r {
+                diff.push(
+                    format!("frames[{}].pixels", index),
+                    json!(format!("{:016x}", l)),
+                    json!(format!("{:016x}", r)),
+                    Some("Rendered frames differ".to_string()),
+                );
+            }
+        }
+    }
+
+    Ok(GifDiff {
+        differences: diff.0,
+        pixels_compared: options.compare_pixels,
+    })
+}
+
+fn diff_metadata(diff: &mut Differences, left: &GifMetadata, right: &GifMetadata) {
+    let dimensions = |meta: &GifMetadata| format!("{}×{}", meta.width, meta.height);
+    diff.field("dimensions", dimensions(left), dimensions(right));
+    diff.field("frame_count", left.frame_count, right.frame_count);
+    diff.field(
+        "total_duration_cs",
+        left.total_duration_cs,
+        right.total_duration_cs,
+    );
+    diff.field("loop_count", left.loop_count, right.loop_count);
+    diff.field(
+        "loop_identifier",
+        &left.loop_identifier,
+        &right.loop_identifier,
+    );
+    diff.field(
+        "loop_buffer_size",
+        left.loop_buffer_size,
+        right.loop_buffer_size,
+    );
+}
+
+fn diff_frame(diff: &mut Differences, index: usize, left: &FrameMeta, right: &FrameMeta) {
+    let path = |field: &str| format!("frames[{}].{}", index, field);
+    let position = |f: &FrameMeta| format!("{}×{} at {},{}", f.width, f.height, f.left, f.top);
+    let texts =
+        |f: &FrameMeta| -> Vec<String> { f.plain_text.iter().map(|t| t.text.clone()).collect() };
+
+    diff.field(path("delay_cs"), left.delay_cs, right.delay_cs);
+    diff.field(path("disposal"), left.disposal, right.disposal);
+    diff.field(
+        path("transparent_index"),
+        left.transparent_index,
+        right.transparent_index,
+    );
+    diff.field(path("position"), position(left), position(right));
+    diff.field(path("plain_text"), texts(left), texts(right));
+}
+
+fn palette_bytes<'a>(gif: &'a GifFile, palette: Option<&Palette>) -> Option<&'a [u8]> {
+    palette.map(|p| gif.bytes(p.span))
+}
+
+/// Records a palette difference as the entry counts, noting how many colors
+/// changed when both have the same size.
+fn diff_palette(
+    diff: &mut Differences,
+    path: impl Into<String>,
+    left: Option<&[u8]>,
+    right: Option<&[u8]>,
+) {
+    if left == right {
+        return;
+    }
+    let entries = |p: Option<&[u8]>| json!(p.map(|p| p.len() / 3));
+    let note = match (left, right) {
+        (Some(l), Some(r)) if l.len() == r.len() => {
+            let changed = l.chunks(3).zip(r.chunks(3)).filter(|(a, b)| a != b).count();
+            Some(format!("{} of {} colors differ", changed, l.len() / 3))
+        }
+        _ => None,
+    };
+    diff.push(path, entries(left), entries(right), note);
+}
+
+/// Application extensions (other than the loop extension, compared through
+/// the loop fields) and unknown extensions, as name and payload.
+fn extensions(gif: &GifFile) -> Vec<(String, Vec<u8>)> {
+    gif.blocks
+        .iter()
+        .filter_map(|block| match block {
+            Block::Application(app)
+                if !loop_count::LOOP_EXTENSIONS.contains(&app.identifier.as_str()) =>
+            {
+                Some((app.identifier.clone(), app.data.collect(&gif.data)))
+            }
+            Block::Extension(ext) => {
+                Some((format!("0x{:02X}", ext.label), ext.data.collect(&gif.data)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Compares extensions by name, reporting the payload sizes of each side.
+fn diff_extensions(diff: &mut Differences, left: &GifFile, right: &GifFile) {
+    let (left, right) = (extensions(left), extensions(right));
+    let mut names: Vec<&String> = Vec::new();
+    for (name, _) in left.iter().chain(&right) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    for name in names {
+        let payloads = |list: &[(String, Vec<u8>)]| -> Vec<Vec<u8>> {
+            list.iter()
+                .filter(|(n, _)| n == name)
+                .map(|(_, data)| data.clone())
+                .collect()
+        };
+        let (l, r) = (payloads(&left), payloads(&right));
+        if l == r {
+            continue;
+        }
+        let sizes = |p: &[Vec<u8>]| -> Vec<usize> { p.iter().map(Vec::len).collect() };
+        let (left_sizes, right_sizes) = (sizes(&l), sizes(&r));
+        let note = (left_sizes == right_sizes).then(|| "Same size, different contents".to_string());
+        diff.push(
+            format!("extensions[{}]", name),
+            json!(left_sizes),
+            json!(right_sizes),
+            note,
+        );
+    }
+}
+
+/// Hashes the rendered canvas after every frame.
+fn frame_hashes(gif: &GifFile) -> Result<Vec<u64>, GifError> {
+    let mut hashes = Vec::new();
+    render_frames(&gif.data, &PreviewOptions::default(), |_, canvas| {
+        let mut hasher = DefaultHasher::new();
+        canvas.dimensions().hash(&mut hasher);
+        canvas.as_raw().hash(&mut hasher);
+        hashes.push(hasher.finish());
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(hashes)
+}
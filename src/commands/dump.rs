@@ -0,0 +1,191 @@
+// src/commands/dump.rs
+
+use serde_json::{json, Map, Value};
+
+use crate::blocks::{Block, GifFile, Span};
+use crate::gifmeta_structs::BlockDump;
+use crate::utils::{icc, loop_count, xmp};
+
+fn entry(gif: &GifFile, span: Span, kind: &str, name: &str, fields: Value, hex: bool) -> BlockDump {
+    let fields = match fields {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+    BlockDump {
+        offset: span.offset,
+        len: span.len,
+        kind: kind.to_string(),
+        name: name.to_string(),
+        fields,
+        hex: hex.then(|| {
+            gif.bytes(span)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect()
+        }),
+    }
+}
+
+/// Decoded fields of an Application Extension, including the payload of the
+/// loop, XMP and ICC extensions gifmeta understands.
+fn application_fields(gif: &GifFile, block: &Block) -> Value {
+    let Block::Application(app) = block else {
+        return Value::Null;
+    };
+    let mut fields = json!({
+        "identifier": app.identifier,
+        "sub_blocks": app.data.chunks.len(),
+        "data_len": app.data.data_len(),
+    });
+    let extra = match app.identifier.as_str() {
+        id if loop_count::LOOP_EXTENSIONS.contains(&id) => loop_count::loop_blocks(gif)
+            .into_iter()
+            .find(|b| b.offset == app.span.offset)
+            .map(|b| json!({ "repeat": b.repeat, "buffer_size": b.buffer_size })),
+        xmp::XMP_IDENTIFIER => xmp::xmp_packet(gif, app)
+            .ok()
+            .map(|packet| json!({ "xmp_len": packet.len() })),
+        icc::ICC_IDENTIFIER => {
+            let profile = app.data.collect(&gif.data);
+            Some(json!({ "description": icc::icc_description(&profile) }))
+        }
+        _ => None,
+    };
+    if let (Some(Value::Object(extra)), Value::Object(map)) = (extra, &mut fields) {
+        map.extend(extra);
+    }
+    fields
+}
+
+/// Lists every structural element of `gif` in file order with its decoded fields.
+///
+/// Graphic Control Extensions and images carry the index of the frame they
+/// belong to. With `hex`, each element also includes its raw bytes.
+pub fn dump_blocks(gif: &GifFile, hex: bool) -> Vec<BlockDump> {
+    let screen = &gif.screen;
+    let mut entries = vec![
+        entry(
+            gif,
+            gif.header.span,
+            "header",
+            "Header",
+            json!({ "version": gif.header.version }),
+            hex,
+        ),
+        entry(
+            gif,
+            screen.span,
+            "logical_screen",
+            "Logical Screen Descriptor",
+            json!({
+                "width": screen.width,
+                "height": screen.height,
+                "global_palette": screen.has_global_palette,
+                "global_palette_entries": screen.global_palette_entries,
+                "color_resolution": screen.color_resolution,
+                "sorted": screen.sorted,
+                "background_index": screen.background_index,
+                "pixel_aspect_ratio": screen.pixel_aspect_ratio,
+            }),
+            hex,
+        ),
+    ];
+    if let Some(palette) = &gif.global_palette {
+        entries.push(entry(
+            gif,
+            palette.span,
+            "global_palette",
+            "Global Color Table",
+            json!({ "entries": palette.entries }),
+            hex,
+        ));
+    }
+
+    let mut frame = 0;
+    for (i, block) in gif.blocks.iter().enumerate() {
+        let fields = match block {
+            Block::GraphicControl(gce) => {
+                // A GCE belongs to the next image, unless another GCE comes first
+                let next = gif.blocks[i + 1..]
+                    .iter()
+                    .find(|b| matches!(b, Block::Image(_) | Block::GraphicControl(_)));
+                json!({
+                    "frame": matches!(next, Some(Block::Image(_))).then_some(frame),
+                    "delay_cs": gce.delay_cs,
+                    "disposal": gce.disposal,
+                    "user_input": gce.user_input,
+                    "transparent_index": gce.transparent_index,
+                })
+            }
+            Block::Comment(comment) => {
+                let bytes = comment.data.collect(&gif.data);
+                json!({
+                    "text": String::from_utf8_lossy(&bytes),
+                    "data_len": bytes.len(),
+                })
+            }
+            Block::Application(_) => application_fields(gif, block),
+            Block::PlainText(text) => json!({
+                "left": text.left,
+                "top": text.top,
+                "width": text.width,
+                "height": text.height,
+                "cell_width": text.cell_width,
+                "cell_height": text.cell_height,
+                "foreground_index": text.foreground_index,
+                "background_index": text.background_index,
+                "text": String::from_utf8_lossy(&text.data.collect(&gif.data)),
+            }),
+            Block::Extension(ext) => json!({
+                "label": format!("0x{:02X}", ext.label),
+                "sub_blocks": ext.data.chunks.len(),
+                "data_len": ext.data.data_len(),
+            }),
+            Block::Image(img) => {
+                frame += 1;
+                json!({
+                    "frame": frame - 1,
+                    "left": img.left,
+                    "top": img.top,
+                    "width": img.width,
+                    "height": img.height,
+                    "interlaced": img.interlaced,
+                    "local_palette_entries": img.local_palette.as_ref().map(|p| p.entries),
+                    "lzw_min_code_size": img.lzw_min_code_size,
+                    "sub_blocks": img.data.chunks.len(),
+                    "data_len": img.data.data_len(),
+                })
+            }
+        };
+        entries.push(entry(
+            gif,
+            block.span(),
+            block.kind(),
+            block.name(),
+            fields,
+            hex,
+        ));
+    }
+
+    if let Some(offset) = gif.trailer {
+        entries.push(entry(
+            gif,
+            Span::new(offset, 1),
+            "trailer",
+            "Trailer",
+            json!({}),
+            hex,
+        ));
+    }
+    if let Some(span) = gif.trailing {
+        entries.push(entry(
+            gif,
+            span,
+            "trailing",
+            "Trailing Bytes",
+            json!({}),
+            hex,
+        ));
+    }
+    entries
+}
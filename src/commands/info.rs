@@ -1,10 +1,12 @@
 use gif::DecodeOptions;
-use std::fs::File;
-use std::io::BufReader;
+use std::fs;
+use std::io::Cursor;
 use std::path::Path;
 
-use crate::gifmeta_structs;
-use crate::utils::loop_count;
+use crate::blocks;
+use crate::commands::repair;
+use crate::gifmeta_structs::{self, GifError, LoopExtension, PlayCount};
+use crate::utils::{comments, icc, loop_count, plain_text, xmp};
 
 /// Extracts GIF metadata (dimensions, frame count, duration).
 ///
@@ -13,18 +15,58 @@ use crate::utils::loop_count;
 ///
 /// # Returns
 /// * `Ok(GifMetadata)` on success
-/// * `Err(String)` if file cannot be read or decoded
+/// * `Err(GifError)` if file cannot be read or decoded
 pub fn get_metadata(
     path: &Path,
     show_frames: bool,
-) -> Result<gifmeta_structs::GifMetadata, String> {
-    let file = File::open(path).map_err(|_| "❌ Failed to open file")?;
+) -> Result<gifmeta_structs::GifMetadata, GifError> {
+    let data = fs::read(path).map_err(|e| GifError::io(path, e))?;
+    metadata_from_bytes(data, show_frames)
+}
+
+/// Like [`get_metadata`], but a damaged file is repaired in memory first.
+///
+/// Metadata then describes everything that could be salvaged, and `warnings`
+/// explains what was wrong and what had to be dropped. Only a file too
+/// damaged to repair (or unreadable) is an error.
+pub fn get_metadata_tolerant(
+    path: &Path,
+    show_frames: bool,
+) -> Result<gifmeta_structs::GifMetadata, GifError> {
+    let data = fs::read(path).map_err(|e| GifError::io(path, e))?;
+    tolerant_metadata_from_bytes(data, show_frames)
+}
+
+/// Like [`metadata_from_bytes`], but damaged data is repaired in memory first.
+pub fn tolerant_metadata_from_bytes(
+    data: Vec<u8>,
+    show_frames: bool,
+) -> Result<gifmeta_structs::GifMetadata, GifError> {
+    match metadata_from_bytes(data.clone(), show_frames) {
+        Ok(meta) => Ok(meta),
+        Err(original) => {
+            let (repaired, fixes) = repair::repair_data(data)?;
+            let mut meta = metadata_from_bytes(repaired, show_frames)?;
+            meta.warnings.push(format!("File is damaged: {}", original));
+            meta.warnings.extend(
+                fixes
+                    .into_iter()
+                    .map(|fix| format!("{} (offset {})", fix.message, fix.offset)),
+            );
+            Ok(meta)
+        }
+    }
+}
+
+/// Extracts metadata from the raw bytes of a GIF.
+pub fn metadata_from_bytes(
+    data: Vec<u8>,
+    show_frames: bool,
+) -> Result<gifmeta_structs::GifMetadata, GifError> {
     let mut decoder = DecodeOptions::new();
     decoder.set_color_output(gif::ColorOutput::Indexed);
     decoder.allow_unknown_blocks(true);
-    let mut reader = decoder
-        .read_info(BufReader::new(file))
-        .map_err(|_| "❌ Failed to decode GIF")?;
+    let mut reader = decoder.read_info(Cursor::new(&data))?;
 
     let global_palette = reader.global_palette();
     let has_global_palette = global_palette.is_some();
@@ -35,10 +77,7 @@ pub fn get_metadata(
     let mut total_duration = 0;
     let mut frames = Vec::new();
 
-    while let Some(frame) = reader
-        .read_next_frame()
-        .map_err(|_| "❌ Error reading frame")?
-    {
+    while let Some(frame) = reader.read_next_frame()? {
         if frame.transparent.is_some() {
             uses_transparency = true;
         }
@@ -48,6 +87,12 @@ pub fn get_metadata(
                 index: frame_count,
                 delay_cs: frame.delay,
                 transparent_index: frame.transparent,
+                disposal: frame.dispose as u8,
+                left: frame.left,
+                top: frame.top,
+                width: frame.width,
+                height: frame.height,
+                plain_text: Vec::new(),
             });
         }
 
@@ -55,21 +100,47 @@ pub fn get_metadata(
         total_duration += frame.delay as u32;
     }
 
-    let loop_count = loop_count::extract_loop_count(path).unwrap_or(0);
-    let display_loop_count = match loop_count {
-        0 => 0,     // Infinite looping (by GIF standard)
-        n => n + 1, // Normalize to user-facing loop count
+    let (width, height) = (reader.width(), reader.height());
+    drop(reader);
+    let gif = blocks::parse(data)?;
+    let plain_text = plain_text::read_plain_text(&gif);
+    for text in &plain_text {
+        if let Some(frame) = frames.get_mut(text.frame) {
+            frame.plain_text.push(text.clone());
+        }
+    }
+    let loop_extension = loop_count::read_loop_extension(&gif);
+    let loop_block = loop_count::loop_blocks(&gif).into_iter().next();
+    let total_plays = PlayCount::from_extension(&loop_extension);
+    let display_loop_count = match total_plays {
+        Some(PlayCount::Infinite) => 0,
+        Some(PlayCount::Times(n)) => n.min(u16::MAX as u32) as u16,
+        None => 1, // Malformed extensions are ignored by most decoders
+    };
+    let repeat_count = match loop_extension {
+        LoopExtension::Count { repeat, .. } => Some(repeat),
+        _ => None,
     };
 
     Ok(gifmeta_structs::GifMetadata {
-        width: reader.width(),
-        height: reader.height(),
+        width,
+        height,
         frame_count: frame_count.try_into().unwrap(),
         total_duration_cs: total_duration,
         loop_count: display_loop_count,
-        frames: frames,
-        has_global_palette: has_global_palette,
-        global_palette_size: global_palette_size,
-        uses_transparency: uses_transparency,
+        has_loop_extension: loop_extension != LoopExtension::Missing,
+        loop_identifier: loop_block.as_ref().map(|b| b.identifier.clone()),
+        loop_buffer_size: loop_block.and_then(|b| b.buffer_size),
+        repeat_count,
+        total_plays,
+        frames,
+        has_global_palette,
+        global_palette_size,
+        uses_transparency,
+        comments: comments::read_comments(&gif),
+        plain_text,
+        xmp_size: xmp::read_xmp_bytes(&gif).ok().flatten().map(|x| x.len()),
+        icc_profile: icc::icc_meta(&gif),
+        warnings: Vec::new(),
     })
 }
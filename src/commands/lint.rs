@@ -0,0 +1,312 @@
+// src/commands/lint.rs
+
+use crate::blocks::{self, Block, GifFile, Image};
+use crate::gifmeta_structs::{GifError, LintFinding, LintReport, Severity};
+use crate::utils::loop_count;
+
+/// Collects findings for a single file.
+#[derive(Default)]
+struct Findings(Vec<LintFinding>);
+
+impl Findings {
+    fn push(&mut self, code: &'static str, severity: Severity, offset: usize, message: String) {
+        self.0.push(LintFinding {
+            code,
+            severity,
+            offset,
+            message,
+        });
+    }
+
+    fn error(&mut self, code: &'static str, offset: usize, message: impl Into<String>) {
+        self.push(code, Severity::Error, offset, message.into());
+    }
+
+    fn warning(&mut self, code: &'static str, offset: usize, message: impl Into<String>) {
+        self.push(code, Severity::Warning, offset, message.into());
+    }
+
+    fn info(&mut self, code: &'static str, offset: usize, message: impl Into<String>) {
+        self.push(code, Severity::Info, offset, message.into());
+    }
+}
+
+/// Checks raw GIF bytes against the GIF89a spec and common decoder expectations.
+///
+/// A file that cannot be parsed at all yields a single `parse-error` finding
+/// at the offending offset. Findings are sorted by offset.
+pub fn lint_data(data: Vec<u8>) -> LintReport {
+    let mut findings = Findings::default();
+    match blocks::parse(data) {
+        Ok(gif) => lint_file(&gif, &mut findings),
+        Err(GifError::Decode {
+            offset, message, ..
+        }) => findings.error("parse-error", offset.unwrap_or(0), message),
+        Err(e) => findings.error("parse-error", 0, e.to_string()),
+    }
+
+    let mut findings = findings.0;
+    findings.sort_by_key(|f| f.offset);
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+    LintReport {
+        errors: count(Severity::Error),
+        warnings: count(Severity::Warning),
+        findings,
+    }
+}
+
+fn lint_file(gif: &GifFile, findings: &mut Findings) {
+    let version = gif.header.version.as_str();
+    if version != "87a" && version != "89a" {
+        findings.warning(
+            "unknown-version",
+            0,
+            format!("Unknown GIF version {:?} (expected 87a or 89a)", version),
+        );
+    }
+
+    let screen = &gif.screen;
+    if screen.width == 0 || screen.height == 0 {
+        findings.warning(
+            "zero-size-screen",
+            screen.span.offset,
+            format!(
+                "Logical screen is {}×{}; decoders fall back to the first frame's size",
+                screen.width, screen.height
+            ),
+        );
+    }
+    if gif.global_palette.is_some()
+        && screen.background_index as usize >= screen.global_palette_entries
+    {
+        findings.warning(
+            "background-index-out-of-range",
+            screen.span.offset,
+            format!(
+                "Background color index {} is beyond the {}-entry global palette",
+                screen.background_index, screen.global_palette_entries
+            ),
+        );
+    }
+
+    if version == "87a" {
+        if let Some(ext) = gif.blocks.iter().find(|b| !matches!(b, Block::Image(_))) {
+            findings.warning(
+                "extensions-in-87a",
+                ext.span().offset,
+                format!(
+                    "GIF87a file contains a {} (extensions require GIF89a)",
+                    ext.name()
+                ),
+            );
+        }
+    }
+
+    lint_blocks(gif, findings);
+    lint_loop_extensions(gif, findings);
+
+    match gif.trailer {
+        None => findings.error(
+            "missing-trailer",
+            gif.data.len(),
+            "File ends without a trailer (0x3B)",
+        ),
+        Some(_) => {
+            if let Some(span) = gif.trailing {
+                findings.warning(
+                    "trailing-data",
+                    span.offset,
+                    format!("{} byte(s) after the trailer", span.len),
+                );
+            }
+        }
+    }
+}
+
+fn lint_blocks(gif: &GifFile, findings: &mut Findings) {
+    let mut pending_gce = None;
+    let mut frame = 0;
+
+    for block in &gif.blocks {
+        match block {
+            Block::GraphicControl(gce) => {
+                if let Some(previous) = pending_gce.replace(gce) {
+                    findings.warning(
+                        "gce-without-image",
+                        previous.span.offset,
+                        "Graphic Control Extension is not followed by an image",
+                    );
+                }
+                if gce.disposal > 3 {
+                    findings.warning(
+                        "reserved-disposal",
+                        gce.span.offset,
+                        format!("Disposal method {} is reserved", gce.disposal),
+                    );
+                }
+            }
+            Block::PlainText(text) => {
+                pending_gce = None;
+                if text.cell_width == 0 || text.cell_height == 0 {
+                    findings.warning(
+                        "zero-size-text-cell",
+                        text.span.offset,
+                        "Plain Text Extension has a zero-size character cell",
+                    );
+                }
+            }
+            Block::Application(app) if app.header.len != 11 => findings.warning(
+                "application-block-size",
+                app.span.offset,
+                format!(
+                    "Application Extension {:?} has block size {} (expected 11)",
+                    app.identifier, app.header.len
+                ),
+            ),
+            Block::Extension(ext) => findings.info(
+                "unknown-extension",
+                ext.span.offset,
+                format!("Extension with unknown label 0x{:02X}", ext.label),
+            ),
+            Block::Image(img) => {
+                let transparent = pending_gce.take().and_then(|gce| gce.transparent_index);
+                lint_image(gif, img, frame, transparent, findings);
+                frame += 1;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(gce) = pending_gce {
+        findings.warning(
+            "gce-without-image",
+            gce.span.offset,
+            "Graphic Control Extension is not followed by an image",
+        );
+    }
+    if frame == 0 {
+        findings.error(
+            "no-frames",
+            gif.trailer.unwrap_or(gif.data.len()),
+            "File contains no images",
+        );
+    }
+}
+
+fn lint_image(
+    gif: &GifFile,
+    img: &Image,
+    frame: usize,
+    transparent: Option<u8>,
+    findings: &mut Findings,
+) {
+    let offset = img.span.offset;
+
+    if img.width == 0 || img.height == 0 {
+        findings.error(
+            "zero-size-frame",
+            offset,
+            format!("Frame {} is {}×{}", frame, img.width, img.height),
+        );
+    }
+    let (right, bottom) = (
+        img.left as u32 + img.width as u32,
+        img.top as u32 + img.height as u32,
+    );
+    if right > gif.screen.width as u32 || bottom > gif.screen.height as u32 {
+        findings.warning(
+            "frame-outside-screen",
+            offset,
+            format!(
+                "Frame {} ({}×{} at {},{}) extends outside the {}×{} logical screen",
+                frame,
+                img.width,
+                img.height,
+                img.left,
+                img.top,
+                gif.screen.width,
+                gif.screen.height
+            ),
+        );
+    }
+
+    let palette_entries = img
+        .local_palette
+        .as_ref()
+        .or(gif.global_palette.as_ref())
+        .map(|p| p.entries);
+    match palette_entries {
+        None => findings.error(
+            "missing-palette",
+            offset,
+            format!(
+                "Frame {} has no local palette and there is no global palette",
+                frame
+            ),
+        ),
+        Some(entries) => {
+            if let Some(index) = transparent.filter(|&i| i as usize >= entries) {
+                findings.warning(
+                    "transparent-index-out-of-range",
+                    offset,
+                    format!(
+                        "Frame {} uses transparent index {} but its palette has {} entries",
+                        frame, index, entries
+                    ),
+                );
+            }
+        }
+    }
+
+    if !(2..=8).contains(&img.lzw_min_code_size) {
+        findings.error(
+            "lzw-code-size",
+            img.lzw_offset,
+            format!(
+                "Frame {} has LZW minimum code size {} (must be 2-8)",
+                frame, img.lzw_min_code_size
+            ),
+        );
+    }
+    if img.data.data_len() == 0 && img.width > 0 && img.height > 0 {
+        findings.error(
+            "empty-image-data",
+            img.lzw_offset,
+            format!("Frame {} has no image data", frame),
+        );
+    }
+}
+
+fn lint_loop_extensions(gif: &GifFile, findings: &mut Findings) {
+    let loop_blocks = loop_count::loop_blocks(gif);
+    for block in loop_blocks.iter().skip(1) {
+        findings.warning(
+            "duplicate-loop-extension",
+            block.offset,
+            format!(
+                "Additional {} loop extension; decoders only honour the first",
+                block.identifier
+            ),
+        );
+    }
+    for block in &loop_blocks {
+        if block.repeat.is_none() {
+            findings.warning(
+                "malformed-loop-extension",
+                block.offset,
+                format!("{} block has no valid loop sub-block", block.identifier),
+            );
+        }
+    }
+
+    let first_image = gif.images().next().map(|img| img.span.offset);
+    if let (Some(first), Some(image)) = (loop_blocks.first(), first_image) {
+        if first.offset > image {
+            findings.warning(
+                "loop-extension-after-image",
+                first.offset,
+                "Loop extension comes after the first image; some decoders ignore it",
+            );
+        }
+    }
+}
@@ -1,2 +1,8 @@
+pub mod diff;
+pub mod dump;
 pub mod info;
+pub mod lint;
 pub mod modify;
+pub mod repair;
+pub mod strip;
+pub mod verify;
@@ -1,102 +1,403 @@
 // src/commands/modify.rs
 
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::fs;
 use std::path::PathBuf;
 
-use gif::{DecodeOptions, Encoder, Repeat};
-use uuid::Uuid;
-
-use crate::commands::info;
-
-//use crate::commands::info;
-//use crate::gifmeta_structs::{FrameMeta, GifMetadata};
+use crate::blocks::{self, Block, GifFile};
+use crate::commands::verify;
+use crate::gifmeta_structs::{
+    BlockChange, BlockSummary, DelayChange, GifError, LoopChange, ModifyOptions, ModifyPlan,
+    ModifyReport, PlayCount, TimingReport,
+};
+use crate::utils::loop_count;
+use crate::utils::patch::{self, LoopChanges, LoopEdit, Patch};
+use crate::utils::{comments, icc, in_place, set_frame_delay, xmp};
 
 /// Applies modifications to a GIF file, including loop count, global delay, and specific frame delays.
 ///
-/// If `output` is None, writes to a temp file instead of overwriting input.
+/// Loop count and delays are metadata-only changes, so they are written by patching the
+/// NETSCAPE and Graphic Control Extension bytes in place; image data and every other block
+/// are copied verbatim. Comments, application extensions (XMP, ICC, ...), plain text and
+/// unknown extensions keep their original order; the returned report lists them and flags
+/// anything that did not make it into the output.
+///
+/// If `output` is None, the result is written to a new file in the system
+/// temp directory and the input is left alone.
 ///
 /// # Arguments
 /// - `input`: Path to the input `.gif`
-/// - `loop_count`: Optional new loop count (0 = infinite)
-/// - `global_delay`: Optional new delay for all frames (centiseconds)
-/// - `frame_delays`: Optional list of (index, delay) overrides
+/// - `options`: Loop count, loop removal, global delay and per-frame delay overrides
 /// - `output`: Optional path to save modified GIF
 pub fn apply_modifications(
     input: &PathBuf,
-    loop_count: Option<u16>,
-    global_delay: Option<u16>,
-    frame_delays: Option<HashMap<usize, u16>>,
+    options: &ModifyOptions,
     output: Option<PathBuf>,
-) -> Result<(), String> {
-    // Load GIF metadata + frames
-    let original_loop_count = info::get_metadata(input, false)
-        .ok()
-        .map(|meta| meta.loop_count)
-        .unwrap_or(1); // fallback if metadata fails
-    let file = File::open(input).map_err(|e| format!("Failed to open input: {}", e))?;
-    let mut decoder = DecodeOptions::new();
-    decoder.set_color_output(gif::ColorOutput::Indexed);
-    decoder.allow_unknown_blocks(true);
-    let mut reader = decoder
-        .read_info(BufReader::new(file))
-        .map_err(|e| format!("Decode error: {}", e))?;
-
-    // Decide output file
-    let out_path = output
-        .clone()
-        .unwrap_or_else(|| std::env::temp_dir().join(format!("{}.mod.gif", Uuid::new_v4())));
-    let out_file =
-        File::create(&out_path).map_err(|e| format!("Failed to create output: {}", e))?;
-    let mut writer = BufWriter::new(out_file);
-
-    // Init encoder with global palette
-    let mut encoder = Encoder::new(
-        &mut writer,
-        reader.width(),
-        reader.height(),
-        reader.global_palette().unwrap_or(&[]),
-    )
-    .map_err(|e| format!("Encoder init error: {}", e))?;
-
-    // Apply loop count if specified
-    let effective_loop_count = loop_count.unwrap_or(original_loop_count);
-    let repeat_mode = if effective_loop_count == 0 {
-        Repeat::Infinite
-    } else {
-        Repeat::Finite(effective_loop_count - 1)
+) -> Result<ModifyReport, GifError> {
+    let data = fs::read(input).map_err(|e| GifError::io(input, e))?;
+    let (data, mut report) = modify_bytes(data, options)?;
+
+    let out_path = in_place::write_or_temp(output.as_deref(), "mod", &data)?;
+
+    println!("✅ Modifications applied → {}", out_path.display());
+    report.output = Some(out_path);
+    Ok(report)
+}
+
+/// Resolves the new delay of every frame an option touches, keyed by 0-based
+/// index, from the current delay of each frame.
+///
+/// `delay_all` comes first, then the `delays` overrides, then each of the
+/// `frame_delays` changes in order, so relative changes build on what came
+/// before them. `fps` then replaces every delay with even timing, and `speed`
+/// and `duration` rescale the result. Rescaling carries rounding error from
+/// frame to frame so the total stays accurate.
+pub(crate) fn resolve_delays(options: &ModifyOptions, current: &[u16]) -> HashMap<usize, u16> {
+    let mut delays = HashMap::new();
+    if let Some(d) = options.delay_all {
+        delays.extend((0..current.len()).map(|index| (index, d)));
+    }
+    if let Some(overrides) = &options.delays {
+        delays.extend(
+            overrides
+                .iter()
+                .filter(|(index, _)| **index < current.len()),
+        );
+    }
+    for entry in &options.frame_delays {
+        for index in entry.frames.frames(current.len()) {
+            let delay = delays.get(&index).copied().unwrap_or(current[index]);
+            delays.insert(index, entry.op.apply(delay));
+        }
+    }
+    if options.fps.is_none() && options.speed.is_none() && options.duration.is_none() {
+        return delays;
+    }
+
+    let mut all: Vec<u16> = (0..current.len())
+        .map(|index| delays.get(&index).copied().unwrap_or(current[index]))
+        .collect();
+    if let Some(fps) = options.fps {
+        all = set_frame_delay::scale_delays(&vec![1; all.len()], 100.0 / fps);
+    }
+    if let Some(speed) = options.speed {
+        all = set_frame_delay::scale_delays(&all, 1.0 / speed);
+    }
+    if let Some(duration) = options.duration {
+        let target_cs = duration.as_secs_f64() * 100.0;
+        let total: u32 = all.iter().map(|&d| d as u32).sum();
+        all = if total == 0 {
+            // Nothing to scale: spread the duration evenly
+            set_frame_delay::scale_delays(&vec![1; all.len()], target_cs / all.len() as f64)
+        } else {
+            set_frame_delay::scale_delays(&all, target_cs / total as f64)
+        };
+    }
+    all.into_iter().enumerate().collect()
+}
+
+/// The timing `fps` and `duration` asked for next to what `delays` achieves.
+fn timing_report(
+    options: &ModifyOptions,
+    current: &[u16],
+    delays: &HashMap<usize, u16>,
+) -> Option<TimingReport> {
+    let frame_count = current.len();
+    let requested_duration_cs = match (options.duration, options.fps) {
+        (Some(duration), _) => duration.as_secs_f64() * 100.0,
+        (None, Some(fps)) => frame_count as f64 * 100.0 / (fps * options.speed.unwrap_or(1.0)),
+        (None, None) => return None,
     };
-    let _set_repeat = encoder.set_repeat(repeat_mode);
+    let new_delay = |index: usize| delays.get(&index).copied().unwrap_or(current[index]);
+    Some(TimingReport {
+        frame_count,
+        old_duration_cs: current.iter().map(|&d| d as u32).sum(),
+        new_duration_cs: (0..frame_count).map(|i| new_delay(i) as u32).sum(),
+        requested_duration_cs,
+    })
+}
 
-    let mut index = 0;
-    while let Some(frame) = reader
-        .read_next_frame()
-        .map_err(|e| format!("Frame read error: {}", e))?
+/// Applies modifications to the raw bytes of a GIF and returns the new bytes.
+///
+/// Works like [`apply_modifications`] without touching the filesystem; the
+/// report's `output` is `None`.
+pub fn modify_bytes(
+    data: Vec<u8>,
+    options: &ModifyOptions,
+) -> Result<(Vec<u8>, ModifyReport), GifError> {
+    let gif = blocks::parse(data)?;
+    if let Some(profile) = &options.icc_profile {
+        icc::validate_profile(profile)?;
+    }
+    let positive = |value: &f64| value.is_finite() && *value > 0.0;
+    if let Some(speed) = options.speed.filter(|s| !positive(s)) {
+        return Err(GifError::invalid_spec(
+            speed.to_string(),
+            "Speed must be a positive number",
+        ));
+    }
+    if let Some(fps) = options.fps.filter(|f| !positive(f)) {
+        return Err(GifError::invalid_spec(
+            fps.to_string(),
+            "Frame rate must be a positive number",
+        ));
+    }
+    if let Some(duration) = options.duration.filter(|d| d.is_zero()) {
+        return Err(GifError::invalid_spec(
+            format!("{:?}", duration),
+            "Duration must be longer than zero",
+        ));
+    }
+
+    let current: Vec<u16> = gif
+        .frames()
+        .iter()
+        .map(|(gce, _)| gce.map_or(0, |g| g.delay_cs))
+        .collect();
+    let delays = resolve_delays(options, &current);
+    let timing = timing_report(options, &current, &delays);
+
+    // Translate the user-facing loop count into the stored repeat value.
+    // Playing once is expressed by having no loop extension at all.
+    let loop_edit = match options.loop_count {
+        _ if options.remove_loop => LoopEdit::Remove,
+        Some(1) => LoopEdit::Remove,
+        Some(count) => LoopEdit::Set(count.saturating_sub(1)),
+        None => LoopEdit::Keep,
+    };
+
+    if options.loop_buffer_size.is_some()
+        && loop_edit == LoopEdit::Keep
+        && loop_count::loop_blocks(&gif).is_empty()
     {
-        let mut owned = frame.clone();
+        // A buffering sub-block on its own would leave a loop extension without a loop count
+        return Err(GifError::MissingLoopExtension);
+    }
+    let loop_changes = LoopChanges {
+        edit: loop_edit,
+        buffer_size: options.loop_buffer_size,
+        normalize: options.normalize_loop,
+    };
+
+    // Comment edits go first so a comment never lands between a new GCE and its image
+    let mut edits = Patch::new();
+    comments::comment_edits(
+        &gif,
+        &mut edits,
+        options.clear_comments,
+        options.comment.as_deref(),
+        &options.append_comments,
+    );
+    xmp::xmp_edits(
+        &gif,
+        &mut edits,
+        options.xmp.as_deref().map(str::as_bytes),
+        options.strip_xmp,
+    );
+    icc::icc_edits(
+        &gif,
+        &mut edits,
+        options.icc_profile.as_deref(),
+        options.strip_icc,
+    );
+    patch::metadata_edits(&gif, &mut edits, loop_changes, &delays);
+    let written = blocks::parse(edits.apply(&gif.data))?;
 
-        // Set global delay if provided
-        if let Some(d) = global_delay {
-            owned.delay = d;
+    let (preserved, mut dropped) = compare_carried_blocks(&gif, &written);
+    if loop_edit == LoopEdit::Remove || options.normalize_loop {
+        // Removing or merging loop extensions was requested, so it is not a loss
+        dropped.retain(|b| !is_loop_extension(b));
+    }
+    if options.clear_comments || options.comment.is_some() {
+        dropped.retain(|b| b.kind != "comment");
+    }
+    if options.strip_xmp || options.xmp.is_some() {
+        dropped.retain(|b| b.label.as_deref() != Some(xmp::XMP_IDENTIFIER));
+    }
+    if options.strip_icc || options.icc_profile.is_some() {
+        dropped.retain(|b| b.label.as_deref() != Some(icc::ICC_IDENTIFIER));
+    }
+    for block in &dropped {
+        eprintln!(
+            "⚠️  Could not preserve {} block at offset {}",
+            block.label.as_deref().unwrap_or(&block.kind),
+            block.offset
+        );
+    }
+
+    let verification = if options.verify {
+        let report = verify::verify_modifications(&gif.data, &written.data, options)?;
+        if !report.is_ok() {
+            return Err(GifError::VerificationFailed {
+                problems: report.problems,
+            });
         }
+        Some(report)
+    } else {
+        None
+    };
+
+    let report = ModifyReport {
+        output: None,
+        verification,
+        timing,
+        preserved,
+        dropped,
+    };
+    Ok((written.data, report))
+}
+
+/// Works out what [`modify_bytes`] would change without writing anything.
+///
+/// The edit is performed in memory and the result compared with the input,
+/// so the plan describes exactly the bytes a real run would produce.
+pub fn plan_modifications(data: Vec<u8>, options: &ModifyOptions) -> Result<ModifyPlan, GifError> {
+    let before = blocks::parse(data.clone())?;
+    let (output, report) = modify_bytes(data, options)?;
+    let after = blocks::parse(output)?;
+
+    let plays = |gif: &GifFile| PlayCount::from_extension(&loop_count::read_loop_extension(gif));
+    let loop_count = (plays(&before) != plays(&after)).then(|| LoopChange {
+        old: plays(&before),
+        new: plays(&after),
+    });
+
+    let delay = |gce: Option<&blocks::GraphicControl>| gce.map_or(0, |g| g.delay_cs);
+    let delays = before
+        .frames()
+        .into_iter()
+        .zip(after.frames())
+        .enumerate()
+        .filter_map(|(frame, ((old, _), (new, _)))| {
+            let (old_cs, new_cs) = (delay(old), delay(new));
+            (old_cs != new_cs).then_some(DelayChange {
+                frame,
+                old_cs,
+                new_cs,
+            })
+        })
+        .collect();
+
+    let (added, removed, changed) = diff_carried_blocks(&before, &after);
+    Ok(ModifyPlan {
+        original_size: before.data.len(),
+        output_size: after.data.len(),
+        loop_count,
+        delays,
+        timing: report.timing,
+        added,
+        removed,
+        changed,
+        dropped: report.dropped,
+    })
+}
 
-        // Override specific frame delay
-        if let Some(ref overrides) = frame_delays {
-            for (target_idx, new_delay) in overrides.iter() {
-                if *target_idx == index {
-                    owned.delay = *new_delay;
-                }
+/// Lines up the non-image blocks of two files by their exact bytes, in order.
+///
+/// Unmatched blocks of the same kind and label are paired up as changed; the
+/// rest were removed from `before` or added in `after`.
+fn diff_carried_blocks(
+    before: &GifFile,
+    after: &GifFile,
+) -> (Vec<BlockSummary>, Vec<BlockSummary>, Vec<BlockChange>) {
+    let mut added: Vec<_> = carried_blocks(after).into_iter().map(Some).collect();
+    let mut next = 0;
+    let mut removed = Vec::new();
+
+    for (summary, bytes) in carried_blocks(before) {
+        let found = added[next..]
+            .iter()
+            .position(|b| b.as_ref().is_some_and(|(_, other)| *other == bytes));
+        match found {
+            Some(pos) => {
+                next += pos + 1;
+                added[next - 1] = None;
+            }
+            None => removed.push(summary),
+        }
+    }
+    let mut added: Vec<BlockSummary> = added.into_iter().flatten().map(|(s, _)| s).collect();
+
+    let mut changed = Vec::new();
+    removed.retain(|old: &BlockSummary| {
+        match added
+            .iter()
+            .position(|new| new.kind == old.kind && new.label == old.label)
+        {
+            Some(pos) => {
+                changed.push(BlockChange {
+                    before: old.clone(),
+                    after: added.remove(pos),
+                });
+                false
             }
+            None => true,
         }
+    });
+    (added, removed, changed)
+}
+
+/// Lists the non-image blocks of a file with their raw bytes, in file order.
+fn carried_blocks(gif: &GifFile) -> Vec<(BlockSummary, &[u8])> {
+    gif.blocks
+        .iter()
+        .filter_map(|block| {
+            let (kind, label) = match block {
+                Block::Comment(_) => ("comment", None),
+                Block::Application(app) => ("application", Some(app.identifier.clone())),
+                Block::PlainText(_) => ("plain_text", None),
+                Block::Extension(ext) => ("extension", Some(format!("0x{:02X}", ext.label))),
+                Block::GraphicControl(_) | Block::Image(_) => return None,
+            };
+            let span = block.span();
+            let summary = BlockSummary {
+                kind: kind.to_string(),
+                label,
+                offset: span.offset,
+                len: span.len,
+            };
+            Some((summary, gif.bytes(span)))
+        })
+        .collect()
+}
 
-        encoder
-            .write_frame(&owned)
-            .map_err(|e| format!("Frame write error: {}", e))?;
+/// Matches the input's non-image blocks against the output, in order.
+///
+/// A block counts as preserved when the output contains the same bytes at a later
+/// position than the previous match. The loop extension is matched by identifier
+/// only, since its repeat field and identifier are things `mod` edits.
+fn compare_carried_blocks(
+    before: &GifFile,
+    after: &GifFile,
+) -> (Vec<BlockSummary>, Vec<BlockSummary>) {
+    let remaining = carried_blocks(after);
+    let mut next = 0;
+    let mut preserved = Vec::new();
+    let mut dropped = Vec::new();
 
-        index += 1;
+    for (summary, bytes) in carried_blocks(before) {
+        let is_loop = is_loop_extension(&summary);
+        let found = remaining[next..].iter().position(|(other, other_bytes)| {
+            if is_loop {
+                return is_loop_extension(other);
+            }
+            other.kind == summary.kind && other.label == summary.label && *other_bytes == bytes
+        });
+        match found {
+            Some(pos) => {
+                next += pos + 1;
+                preserved.push(summary);
+            }
+            None => dropped.push(summary),
+        }
     }
 
-    println!("✅ Modifications applied → {}", out_path.display());
-    Ok(())
+    (preserved, dropped)
+}
+
+fn is_loop_extension(block: &BlockSummary) -> bool {
+    block
+        .label
+        .as_deref()
+        .is_some_and(|l| loop_count::LOOP_EXTENSIONS.contains(&l))
 }
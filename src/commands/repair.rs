@@ -0,0 +1,254 @@
+// src/commands/repair.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use weezl::{decode, encode, BitOrder};
+
+use crate::blocks::{self, Block, Span, IMAGE_SEPARATOR, TRAILER};
+use crate::gifmeta_structs::{GifError, RepairFix, RepairReport};
+use crate::utils::in_place;
+use crate::utils::patch::{self, Patch};
+
+/// The rows of a truncated frame that could be decoded, re-encoded as a
+/// complete image block.
+struct SalvagedFrame {
+    bytes: Vec<u8>,
+    left: u16,
+    top: u16,
+    width: u16,
+    rows: u16,
+    height: u16,
+}
+
+fn le_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+/// Rebuilds the image block starting at `start`, which runs past the end of `data`.
+///
+/// Every LZW code that arrived is decoded, the frame is cropped to the rows
+/// that are complete, and those rows are re-encoded so the stream ends with a
+/// proper end code and block terminator. Interlaced frames cannot be cropped
+/// this way and are not salvaged.
+fn salvage_frame(data: &[u8], start: usize) -> Option<SalvagedFrame> {
+    let descriptor = data.get(start..start + 10)?;
+    if descriptor[0] != IMAGE_SEPARATOR {
+        return None;
+    }
+    let (left, top) = (le_u16(descriptor, 1), le_u16(descriptor, 3));
+    let (width, height) = (le_u16(descriptor, 5), le_u16(descriptor, 7));
+    let packed = descriptor[9];
+    if packed & 0b0100_0000 != 0 || width == 0 {
+        return None;
+    }
+    let palette_len = if packed & 0b1000_0000 != 0 {
+        3 * (2 << (packed & 0b0111))
+    } else {
+        0
+    };
+    let lzw_offset = start + 10 + palette_len;
+    let min_code_size = *data.get(lzw_offset)?;
+    if !(2..=8).contains(&min_code_size) {
+        return None;
+    }
+
+    // Payload of every sub-block that arrived, the last one possibly cut short
+    let mut payload = Vec::new();
+    let mut pos = lzw_offset + 1;
+    while let Some(&size) = data.get(pos) {
+        if size == 0 {
+            break;
+        }
+        let end = (pos + 1 + size as usize).min(data.len());
+        payload.extend_from_slice(&data[pos + 1..end]);
+        pos = end;
+    }
+
+    let mut pixels = Vec::new();
+    // A truncated stream stops without an end code; keep whatever decoded
+    let _ = decode::Decoder::new(BitOrder::Lsb, min_code_size)
+        .into_vec(&mut pixels)
+        .decode(&payload);
+    let rows = (pixels.len() / width as usize).min(height as usize) as u16;
+    if rows == 0 {
+        return None;
+    }
+    pixels.truncate(rows as usize * width as usize);
+    let lzw = encode::Encoder::new(BitOrder::Lsb, min_code_size)
+        .encode(&pixels)
+        .ok()?;
+
+    let mut bytes = data[start..lzw_offset].to_vec();
+    bytes[7..9].copy_from_slice(&rows.to_le_bytes());
+    bytes.push(min_code_size);
+    bytes.extend(patch::sub_blocks(&lzw));
+    Some(SalvagedFrame {
+        bytes,
+        left,
+        top,
+        width,
+        rows,
+        height,
+    })
+}
+
+/// Turns a truncated or slightly malformed GIF into a valid one.
+///
+/// Every complete block is copied verbatim. Parsing stops at the first damaged
+/// block; if that is a frame cut off mid-stream, its complete rows are kept
+/// and re-encoded. A Graphic Control Extension left without an image is
+/// dropped, the trailer is appended (and anything after it removed), and
+/// header fields are fixed: the version is set to 89a when extensions need
+/// it, the logical screen grows to fit every frame, and an out-of-range
+/// background index is reset to 0.
+///
+/// Fails if not a single frame can be kept, since the result would not be a
+/// usable animation.
+///
+/// Returns the repaired bytes and every fix made, in file order.
+pub fn repair_data(data: Vec<u8>) -> Result<(Vec<u8>, Vec<RepairFix>), GifError> {
+    let (gif, error) = blocks::parse_partial(data)?;
+    let mut edits = Patch::new();
+    let mut fixes = Vec::new();
+    let mut fix = |offset: usize, message: String| fixes.push(RepairFix { offset, message });
+
+    let complete_end = gif
+        .blocks
+        .last()
+        .map_or_else(|| patch::after_screen(&gif), |b| b.span().end());
+    let frame_count = gif.images().count();
+
+    let mut salvaged = None;
+    if let Some(error) = &error {
+        fix(
+            complete_end,
+            format!("Damaged data from here on: {}", error),
+        );
+        salvaged = salvage_frame(&gif.data, complete_end);
+        match &salvaged {
+            Some(frame) if frame.rows < frame.height => fix(
+                complete_end,
+                format!(
+                    "Frame {} was cut after {} of {} rows; kept the complete rows and terminated its LZW stream",
+                    frame_count, frame.rows, frame.height
+                ),
+            ),
+            Some(_) => fix(
+                complete_end,
+                format!("Frame {} was complete; terminated its LZW stream", frame_count),
+            ),
+            None if gif.data[complete_end] == IMAGE_SEPARATOR => fix(
+                complete_end,
+                format!("Dropped frame {}: none of its rows could be recovered", frame_count),
+            ),
+            None => fix(
+                complete_end,
+                format!("Dropped {} damaged byte(s)", gif.data.len() - complete_end),
+            ),
+        }
+        edits.remove(Span::new(complete_end, gif.data.len() - complete_end));
+        if let Some(frame) = &salvaged {
+            edits.insert(complete_end, frame.bytes.clone());
+        }
+        edits.insert(complete_end, vec![TRAILER]);
+        fix(complete_end, "Appended the trailer".to_string());
+    } else if let Some(span) = gif.trailing {
+        edits.remove(span);
+        fix(
+            span.offset,
+            format!("Removed {} byte(s) after the trailer", span.len),
+        );
+    } else if gif.trailer.is_none() {
+        edits.insert(gif.data.len(), vec![TRAILER]);
+        fix(gif.data.len(), "Appended the missing trailer".to_string());
+    }
+
+    if frame_count == 0 && salvaged.is_none() {
+        return Err(GifError::decode(complete_end, "no frame could be salvaged"));
+    }
+
+    if let (Some(Block::GraphicControl(gce)), None) = (gif.blocks.last(), &salvaged) {
+        edits.remove(gce.span);
+        fix(
+            gce.span.offset,
+            "Dropped a Graphic Control Extension with no image after it".to_string(),
+        );
+    }
+
+    let version = gif.header.version.as_str();
+    let has_extensions = gif.blocks.iter().any(|b| !matches!(b, Block::Image(_)));
+    if !(version == "89a" || (version == "87a" && !has_extensions)) {
+        edits.replace(Span::new(3, 3), b"89a".to_vec());
+        fix(3, format!("Changed version {:?} to \"89a\"", version));
+    }
+
+    let screen = &gif.screen;
+    let frames = gif
+        .images()
+        .map(|img| (img.left, img.top, img.width, img.height))
+        .chain(salvaged.iter().map(|f| (f.left, f.top, f.width, f.rows)));
+    let (mut width, mut height) = (screen.width, screen.height);
+    for (left, top, w, h) in frames {
+        width = width.max(left.saturating_add(w));
+        height = height.max(top.saturating_add(h));
+    }
+    if (width, height) != (screen.width, screen.height) {
+        let mut size = width.to_le_bytes().to_vec();
+        size.extend_from_slice(&height.to_le_bytes());
+        edits.replace(Span::new(screen.span.offset, 4), size);
+        fix(
+            screen.span.offset,
+            format!(
+                "Enlarged the logical screen from {}×{} to {}×{} to fit every frame",
+                screen.width, screen.height, width, height
+            ),
+        );
+    }
+    if gif.global_palette.is_some()
+        && screen.background_index as usize >= screen.global_palette_entries
+    {
+        edits.replace(Span::new(screen.span.offset + 5, 1), vec![0]);
+        fix(
+            screen.span.offset + 5,
+            format!(
+                "Reset background index {} (beyond the {}-entry palette) to 0",
+                screen.background_index, screen.global_palette_entries
+            ),
+        );
+    }
+
+    fixes.sort_by_key(|f| f.offset);
+    Ok((edits.apply(&gif.data), fixes))
+}
+
+/// Repairs a GIF file and writes the result.
+///
+/// If `output` is None, the result is written to a new file in the system
+/// temp directory and the input is left alone.
+pub fn repair_file(input: &Path, output: Option<PathBuf>) -> Result<RepairReport, GifError> {
+    let data = fs::read(input).map_err(|e| GifError::io(input, e))?;
+    let (data, mut report) = repair_bytes(data)?;
+
+    report.output = Some(in_place::write_or_temp(output.as_deref(), "repair", &data)?);
+    Ok(report)
+}
+
+/// Repairs the raw bytes of a GIF and returns the repaired bytes.
+///
+/// Works like [`repair_file`] without touching the filesystem; the report's
+/// `output` is `None`.
+pub fn repair_bytes(data: Vec<u8>) -> Result<(Vec<u8>, RepairReport), GifError> {
+    let original_size = data.len();
+    let (repaired, fixes) = repair_data(data)?;
+    let written = blocks::parse(repaired)?;
+
+    let report = RepairReport {
+        output: None,
+        original_size,
+        repaired_size: written.data.len(),
+        frames: written.images().count(),
+        fixes,
+    };
+    Ok((written.data, report))
+}
@@ -0,0 +1,127 @@
+// src/commands/strip.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::blocks::{self, Block, GifFile, Span};
+use crate::gifmeta_structs::{
+    GifError, StripCategory, StripOptions, StripReport, StrippedCategory,
+};
+use crate::utils::patch::Patch;
+use crate::utils::{icc, in_place, loop_count, plain_text, xmp};
+
+/// Sorts a block into a strip category, or `None` if it is needed to render.
+fn category(block: &Block) -> Option<StripCategory> {
+    match block {
+        Block::Application(app) => Some(match app.identifier.as_str() {
+            id if loop_count::LOOP_EXTENSIONS.contains(&id) => StripCategory::Loop,
+            xmp::XMP_IDENTIFIER => StripCategory::Xmp,
+            icc::ICC_IDENTIFIER => StripCategory::Icc,
+            _ => StripCategory::Applications,
+        }),
+        Block::Comment(_) => Some(StripCategory::Comments),
+        Block::PlainText(_) => Some(StripCategory::PlainText),
+        Block::Extension(_) => Some(StripCategory::Extensions),
+        Block::GraphicControl(_) | Block::Image(_) => None,
+    }
+}
+
+/// Every removable block in `gif`, with its category and the spans it covers.
+///
+/// A Plain Text Extension takes its Graphic Control Extension with it, as
+/// that would otherwise apply to the next image, unless no other GCE comes
+/// before that image: the GCE then already applies to it and must stay.
+fn removable_blocks(gif: &GifFile) -> Vec<(StripCategory, Vec<Span>)> {
+    let mut found: Vec<_> = gif
+        .blocks
+        .iter()
+        .filter(|block| !matches!(block, Block::PlainText(_)))
+        .filter_map(|block| category(block).map(|c| (c, vec![block.span()])))
+        .collect();
+    let image_controls: Vec<_> = gif
+        .frames()
+        .into_iter()
+        .filter_map(|(gce, _)| gce.map(|gce| gce.span))
+        .collect();
+    found.extend(plain_text::plain_text_blocks(gif).iter().map(|block| {
+        let mut spans = vec![block.text.span];
+        spans.extend(
+            block
+                .control
+                .map(|gce| gce.span)
+                .filter(|span| !image_controls.contains(span)),
+        );
+        (StripCategory::PlainText, spans)
+    }));
+    found.extend(
+        gif.trailing
+            .map(|span| (StripCategory::Trailing, vec![span])),
+    );
+    found
+}
+
+/// Removes every non-essential block from a GIF file.
+///
+/// Header, palettes, Graphic Control Extensions and image data are copied
+/// byte for byte; blocks in a category listed in `options.keep` are left in
+/// place. Nothing is re-encoded.
+///
+/// If `output` is None, the result is written to a new file in the system
+/// temp directory and the input is left alone.
+///
+/// # Arguments
+/// - `input`: Path to the input `.gif`
+/// - `options`: Categories to keep
+/// - `output`: Optional path to save the stripped GIF
+pub fn strip_metadata(
+    input: &Path,
+    options: &StripOptions,
+    output: Option<PathBuf>,
+) -> Result<StripReport, GifError> {
+    let data = fs::read(input).map_err(|e| GifError::io(input, e))?;
+    let (data, mut report) = strip_bytes(data, options)?;
+
+    report.output = Some(in_place::write_or_temp(output.as_deref(), "strip", &data)?);
+    Ok(report)
+}
+
+/// Strips metadata from the raw bytes of a GIF and returns the new bytes.
+///
+/// Works like [`strip_metadata`] without touching the filesystem; the
+/// report's `output` is `None`.
+pub fn strip_bytes(
+    data: Vec<u8>,
+    options: &StripOptions,
+) -> Result<(Vec<u8>, StripReport), GifError> {
+    let gif = blocks::parse(data)?;
+
+    let mut removed: Vec<StrippedCategory> = StripCategory::ALL
+        .into_iter()
+        .filter(|c| !options.keep.contains(c))
+        .map(|category| StrippedCategory {
+            category,
+            blocks: 0,
+            bytes: 0,
+        })
+        .collect();
+
+    let mut edits = Patch::new();
+    for (category, spans) in removable_blocks(&gif) {
+        if let Some(entry) = removed.iter_mut().find(|e| e.category == category) {
+            entry.blocks += 1;
+            for span in spans {
+                entry.bytes += span.len;
+                edits.remove(span);
+            }
+        }
+    }
+    let written = blocks::parse(edits.apply(&gif.data))?;
+
+    let report = StripReport {
+        output: None,
+        original_size: gif.data.len(),
+        stripped_size: written.data.len(),
+        removed,
+    };
+    Ok((written.data, report))
+}
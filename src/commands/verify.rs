@@ -0,0 +1,131 @@
+// src/commands/verify.rs
+
+use crate::blocks;
+use crate::commands::{diff, info, modify};
+use crate::gifmeta_structs::{DiffOptions, GifError, ModifyOptions, VerifyReport};
+use crate::utils::{icc, loop_count, xmp};
+
+/// Checks that `output` is `original` with exactly the changes in `options`.
+///
+/// Every frame of both files is rendered and must be pixel-identical. The
+/// loop count, buffer size, delays, comments, XMP packet and ICC profile must
+/// hold the requested values, and every other field [`diff::diff_data`]
+/// compares must be unchanged.
+pub fn verify_modifications(
+    original: &[u8],
+    output: &[u8],
+    options: &ModifyOptions,
+) -> Result<VerifyReport, GifError> {
+    let before = info::metadata_from_bytes(original.to_vec(), true)?;
+    let after = info::metadata_from_bytes(output.to_vec(), true)?;
+    let after_gif = blocks::parse(output.to_vec())?;
+    let mut problems = Vec::new();
+
+    // Requested values
+    let removes_loop = options.remove_loop || options.loop_count == Some(1);
+    if removes_loop && after.has_loop_extension {
+        problems.push("Loop extension is still present".to_string());
+    } else if let Some(count) = options.loop_count.filter(|_| !removes_loop) {
+        if after.loop_count != count || !after.has_loop_extension {
+            problems.push(format!(
+                "loop_count is {}, expected {}",
+                after.loop_count, count
+            ));
+        }
+    }
+    if let Some(size) = options.loop_buffer_size {
+        if after.loop_buffer_size != Some(size) {
+            problems.push(format!(
+                "loop_buffer_size is {:?}, expected {}",
+                after.loop_buffer_size, size
+            ));
+        }
+    }
+    if options.normalize_loop && loop_count::loop_blocks(&after_gif).len() > 1 {
+        problems.push("More than one loop extension after normalizing".to_string());
+    }
+
+    let current: Vec<u16> = before.frames.iter().map(|f| f.delay_cs).collect();
+    let delays = modify::resolve_delays(options, &current);
+    for (index, (old, new)) in before.frames.iter().zip(&after.frames).enumerate() {
+        let expected = delays.get(&index).copied().unwrap_or(old.delay_cs);
+        if new.delay_cs != expected {
+            problems.push(format!(
+                "frames[{}].delay_cs is {}, expected {}",
+                index, new.delay_cs, expected
+            ));
+        }
+    }
+
+    let comments_touched =
+        options.clear_comments || options.comment.is_some() || !options.append_comments.is_empty();
+    if comments_touched {
+        let mut expected: Vec<&[u8]> = match &options.comment {
+            Some(comment) => vec![comment.as_bytes()],
+            None if options.clear_comments => Vec::new(),
+            None => before.comments.iter().map(|c| c.bytes.as_slice()).collect(),
+        };
+        expected.extend(options.append_comments.iter().map(|c| c.as_bytes()));
+        let actual: Vec<&[u8]> = after.comments.iter().map(|c| c.bytes.as_slice()).collect();
+        if actual != expected {
+            problems.push(format!(
+                "Output has {} comment(s) that do not match the {} requested",
+                actual.len(),
+                expected.len()
+            ));
+        }
+    }
+
+    let xmp_touched = options.xmp.is_some() || options.strip_xmp;
+    if xmp_touched && xmp::read_xmp(&after_gif)? != options.xmp {
+        problems.push("XMP packet does not match the requested one".to_string());
+    }
+    let icc_touched = options.icc_profile.is_some() || options.strip_icc;
+    if icc_touched && icc::read_icc(&after_gif) != options.icc_profile {
+        problems.push("ICC profile does not match the requested one".to_string());
+    }
+
+    // Everything else must be untouched
+    let loop_touched = options.loop_count.is_some()
+        || options.remove_loop
+        || options.loop_buffer_size.is_some()
+        || options.normalize_loop;
+    let delays_touched = options.delay_all.is_some()
+        || options.delays.is_some()
+        || !options.frame_delays.is_empty()
+        || options.speed.is_some()
+        || options.fps.is_some()
+        || options.duration.is_some();
+    let allowed = |path: &str| match path {
+        "loop_count" | "loop_identifier" | "loop_buffer_size" => loop_touched,
+        "total_duration_cs" => delays_touched,
+        "comments" => comments_touched,
+        "extensions[XMP DataXMP]" => xmp_touched,
+        "extensions[ICCRGBG1012]" => icc_touched,
+        _ => delays_touched && path.starts_with("frames[") && path.ends_with("].delay_cs"),
+    };
+    let diff = diff::diff_data(
+        original.to_vec(),
+        output.to_vec(),
+        &DiffOptions {
+            compare_pixels: true,
+        },
+    )?;
+    for d in diff.differences.iter().filter(|d| !allowed(&d.path)) {
+        problems.push(format!(
+            "{} changed: {} → {}{}",
+            d.path,
+            diff::value_text(&d.left),
+            diff::value_text(&d.right),
+            d.note
+                .as_ref()
+                .map(|n| format!(" ({})", n))
+                .unwrap_or_default()
+        ));
+    }
+
+    Ok(VerifyReport {
+        frames_compared: before.frames.len().min(after.frames.len()),
+        problems,
+    })
+}
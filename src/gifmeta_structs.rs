@@ -1,8 +1,161 @@
 use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
 
-// Define a basic GifError type if not already defined elsewhere
+/// Errors returned by every gifmeta library function.
 #[derive(Debug)]
-pub struct GifError(pub String);
+pub enum GifError {
+    /// Reading or writing a file failed.
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// The data is not a valid GIF. `offset` points at the offending byte when known.
+    Decode {
+        offset: Option<usize>,
+        message: String,
+        source: Option<gif::DecodingError>,
+    },
+    /// A frame index beyond the last frame was requested.
+    FrameOutOfRange { index: usize, frame_count: usize },
+    /// The file has no NETSCAPE2.0 loop extension.
+    MissingLoopExtension,
+    /// A command-line style value (e.g. `--delays`) could not be parsed.
+    InvalidSpec { input: String, message: String },
+    /// `mod` was called without anything to change.
+    NoModifications,
+    /// The output of `mod --verify` changed more (or less) than was requested.
+    VerificationFailed { problems: Vec<String> },
+    /// Encoding an extracted frame as PNG failed.
+    Image(image::ImageError),
+}
+
+impl GifError {
+    pub(crate) fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        GifError::Io {
+            path: Some(path.into()),
+            source,
+        }
+    }
+
+    pub(crate) fn decode(offset: usize, message: impl Into<String>) -> Self {
+        GifError::Decode {
+            offset: Some(offset),
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn invalid_spec(input: impl Into<String>, message: impl Into<String>) -> Self {
+        GifError::InvalidSpec {
+            input: input.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for GifError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GifError::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "I/O error on {}: {}", path.display(), source),
+            GifError::Io { path: None, source } => write!(f, "I/O error: {}", source),
+            GifError::Decode {
+                offset: Some(offset),
+                message,
+                ..
+            } => write!(f, "Failed to decode GIF at offset {}: {}", offset, message),
+            GifError::Decode { message, .. } => write!(f, "Failed to decode GIF: {}", message),
+            GifError::FrameOutOfRange { index, frame_count } => write!(
+                f,
+                "Frame index {} out of bounds (GIF has {} frames)",
+                index, frame_count
+            ),
+            GifError::MissingLoopExtension => write!(f, "Loop count not found"),
+            GifError::InvalidSpec { input, message } => write!(f, "{}: '{}'", message, input),
+            GifError::NoModifications => write!(f, "No modification parameters provided."),
+            GifError::VerificationFailed { problems } => write!(
+                f,
+                "Verification failed with {} problem(s): {}",
+                problems.len(),
+                problems.join("; ")
+            ),
+            GifError::Image(e) => write!(f, "PNG encode error: {}", e),
+        }
+    }
+}
+
+impl Error for GifError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GifError::Io { source, .. } => Some(source),
+            GifError::Decode {
+                source: Some(source),
+                ..
+            } => Some(source),
+            GifError::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<gif::DecodingError> for GifError {
+    fn from(e: gif::DecodingError) -> Self {
+        GifError::Decode {
+            offset: None,
+            message: e.to_string(),
+            source: Some(e),
+        }
+    }
+}
+
+impl From<image::ImageError> for GifError {
+    fn from(e: image::ImageError) -> Self {
+        GifError::Image(e)
+    }
+}
+
+/// What the loop extension of a GIF says, if it has one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LoopExtension {
+    /// No NETSCAPE2.0 / ANIMEXTS1.0 application extension; the GIF plays once.
+    Missing,
+    /// A loop block exists at `offset` but has no valid `0x03 0x01` loop sub-block.
+    Malformed { offset: usize },
+    /// Raw repeat count stored in the block at `offset` (0 = infinite).
+    Count { offset: usize, repeat: u16 },
+}
+
+/// How many times an animation plays in total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayCount {
+    Infinite,
+    Times(u32),
+}
+
+impl PlayCount {
+    /// Derives the total number of plays from the loop extension.
+    ///
+    /// A missing extension means the GIF plays once; a raw repeat of 0 means
+    /// forever; any other raw value N means the first play plus N repeats.
+    /// Returns `None` for a malformed extension, where decoders disagree.
+    pub fn from_extension(ext: &LoopExtension) -> Option<PlayCount> {
+        match ext {
+            LoopExtension::Missing => Some(PlayCount::Times(1)),
+            LoopExtension::Malformed { .. } => None,
+            LoopExtension::Count { repeat: 0, .. } => Some(PlayCount::Infinite),
+            LoopExtension::Count { repeat, .. } => Some(PlayCount::Times(*repeat as u32 + 1)),
+        }
+    }
+}
 
 /// Metadata summary for a GIF file.
 #[derive(Debug, PartialEq, Serialize)]
@@ -11,11 +164,51 @@ pub struct GifMetadata {
     pub height: u16,
     pub frame_count: u32,
     pub total_duration_cs: u32, // centiseconds
+    /// User-facing loop count as accepted by `mod --loop-count`: 0 = infinite, otherwise total plays.
     pub loop_count: u16,
+    /// Whether a NETSCAPE2.0 or ANIMEXTS1.0 loop extension is present.
+    pub has_loop_extension: bool,
+    /// Identifier of the loop extension in use (`NETSCAPE2.0` or `ANIMEXTS1.0`).
+    pub loop_identifier: Option<String>,
+    /// Buffer size from the NETSCAPE buffering sub-block, if present.
+    pub loop_buffer_size: Option<u32>,
+    /// Raw repeat value stored in the loop extension, if it has a valid one.
+    pub repeat_count: Option<u16>,
+    /// Total number of plays; `None` if the loop extension is malformed.
+    pub total_plays: Option<PlayCount>,
     pub frames: Vec<FrameMeta>,
     pub has_global_palette: bool,
     pub global_palette_size: Option<usize>,
     pub uses_transparency: bool,
+    pub comments: Vec<CommentMeta>,
+    /// Every Plain Text Extension, whether or not frames are listed.
+    pub plain_text: Vec<PlainTextMeta>,
+    /// Size in bytes of the embedded XMP packet, if any.
+    pub xmp_size: Option<usize>,
+    /// Embedded ICC color profile, if any.
+    pub icc_profile: Option<IccMeta>,
+    /// Problems worked around when reading a damaged file in tolerant mode.
+    pub warnings: Vec<String>,
+}
+
+/// Summary of an embedded ICC color profile.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IccMeta {
+    /// Profile size in bytes.
+    pub size: usize,
+    /// Text of the profile's `desc` tag, when it can be read.
+    pub description: Option<String>,
+}
+
+/// A Comment Extension and its text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommentMeta {
+    /// Offset of the extension block.
+    pub offset: usize,
+    /// Comment decoded as UTF-8, with invalid sequences replaced.
+    pub text: String,
+    /// The raw comment bytes.
+    pub bytes: Vec<u8>,
 }
 
 /// Metadata for an individual frame.
@@ -24,4 +217,574 @@ pub struct FrameMeta {
     pub index: usize,
     pub delay_cs: u16,
     pub transparent_index: Option<u8>,
+    /// Disposal method (0 = unspecified, 1 = keep, 2 = background, 3 = previous).
+    pub disposal: u8,
+    pub left: u16,
+    pub top: u16,
+    pub width: u16,
+    pub height: u16,
+    /// Plain Text Extensions drawn over this frame.
+    pub plain_text: Vec<PlainTextMeta>,
+}
+
+/// A Plain Text Extension: a grid of text cells drawn onto the canvas.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlainTextMeta {
+    /// Offset of the extension block.
+    pub offset: usize,
+    /// Frame the text is drawn over (the closest image before it).
+    pub frame: usize,
+    /// Text grid position and size, in pixels.
+    pub left: u16,
+    pub top: u16,
+    pub width: u16,
+    pub height: u16,
+    /// Character cell size, in pixels.
+    pub cell_width: u8,
+    pub cell_height: u8,
+    /// Global palette indices of the text and grid colors.
+    pub foreground_index: u8,
+    pub background_index: u8,
+    /// Delay from the preceding Graphic Control Extension, if any.
+    pub delay_cs: Option<u16>,
+    /// Transparent index from the preceding Graphic Control Extension, if any.
+    pub transparent_index: Option<u8>,
+    /// The text, with invalid UTF-8 replaced.
+    pub text: String,
+}
+
+/// Options for rendering a frame preview.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PreviewOptions {
+    /// Rasterize Plain Text Extensions with the built-in bitmap font.
+    pub render_plain_text: bool,
+}
+
+/// Options for replacing a file in place.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InPlaceOptions {
+    /// Keep the original file, renamed with this suffix appended (e.g. `.bak`).
+    pub backup_suffix: Option<String>,
+    /// Give the new file the modification time of the original.
+    pub preserve_mtime: bool,
+}
+
+/// One end of a frame selector, counted from the first or the last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameIndex {
+    /// 0-based index from the first frame (`first` is `FromStart(0)`).
+    FromStart(usize),
+    /// 1-based index from the last frame (`-1` and `last` are `FromEnd(1)`).
+    FromEnd(usize),
+}
+
+impl FrameIndex {
+    /// The 0-based index in a GIF with `frame_count` frames, or None if it
+    /// falls before the first frame.
+    pub fn resolve(&self, frame_count: usize) -> Option<usize> {
+        match *self {
+            FrameIndex::FromStart(index) => Some(index),
+            FrameIndex::FromEnd(back) => frame_count.checked_sub(back),
+        }
+    }
+}
+
+/// Frames picked by a `--delays` selector such as `3`, `-1`, `0-9`, `10-`
+/// or `0-20/2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameSelector {
+    pub start: FrameIndex,
+    /// Last frame of the range (inclusive); None runs to the last frame.
+    /// A single frame has `end == Some(start)`.
+    pub end: Option<FrameIndex>,
+    /// Take every `step`th frame of the range, starting at `start`.
+    pub step: usize,
+}
+
+impl FrameSelector {
+    /// Selects a single frame.
+    pub fn single(index: FrameIndex) -> Self {
+        FrameSelector {
+            start: index,
+            end: Some(index),
+            step: 1,
+        }
+    }
+
+    /// Selects every frame (`0-`).
+    pub fn all() -> Self {
+        FrameSelector {
+            start: FrameIndex::FromStart(0),
+            end: None,
+            step: 1,
+        }
+    }
+
+    /// The 0-based indexes selected in a GIF with `frame_count` frames.
+    ///
+    /// Frames that do not exist are skipped rather than reported, so one
+    /// selector can be applied to GIFs of any length: ranges are cut to the
+    /// frames the GIF has, and a single frame beyond either end selects nothing.
+    pub fn frames(&self, frame_count: usize) -> Vec<usize> {
+        let start = match (
+            self.start.resolve(frame_count),
+            self.end == Some(self.start),
+        ) {
+            (Some(start), _) => start,
+            (None, false) => 0,
+            (None, true) => return Vec::new(),
+        };
+        let end = match self.end {
+            Some(end) => match end.resolve(frame_count) {
+                Some(end) => end.min(frame_count.saturating_sub(1)),
+                None => return Vec::new(),
+            },
+            None => frame_count.saturating_sub(1),
+        };
+        if frame_count == 0 || start > end {
+            return Vec::new();
+        }
+        (start..=end).step_by(self.step.max(1)).collect()
+    }
+}
+
+/// A change to a frame delay, as written after `=` in `--delays` or given to
+/// `--delay`: `5`, `+5`, `-2`, `*1.5`, `/2`, or a clamp `2..10`, `2..`, `..10`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DelayOp {
+    /// Set the delay (centiseconds).
+    Set(u16),
+    /// Add (or subtract, if negative) centiseconds, stopping at 0 and 65535.
+    Add(i32),
+    /// Multiply by a factor and round to the nearest centisecond.
+    Scale(f64),
+    /// Raise delays below `min` and lower delays above `max`.
+    Clamp { min: Option<u16>, max: Option<u16> },
+}
+
+impl DelayOp {
+    /// The new delay for a frame that currently waits `delay_cs`.
+    pub fn apply(&self, delay_cs: u16) -> u16 {
+        match *self {
+            DelayOp::Set(delay) => delay,
+            DelayOp::Add(change) => (delay_cs as i32 + change).clamp(0, u16::MAX as i32) as u16,
+            DelayOp::Scale(factor) => {
+                (delay_cs as f64 * factor).round().min(u16::MAX as f64) as u16
+            }
+            DelayOp::Clamp { min, max } => {
+                delay_cs.max(min.unwrap_or(0)).min(max.unwrap_or(u16::MAX))
+            }
+        }
+    }
+}
+
+/// A delay change for every frame picked by a selector, as parsed from `--delays`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameDelay {
+    pub frames: FrameSelector,
+    pub op: DelayOp,
+}
+
+/// Everything `mod` can change in a single run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModifyOptions {
+    /// New loop count: 0 = infinite, 1 = play once (no loop extension), N = play N times.
+    pub loop_count: Option<u16>,
+    /// Remove any NETSCAPE2.0 / ANIMEXTS1.0 loop extension so the GIF plays once.
+    pub remove_loop: bool,
+    /// Buffer size (bytes) to store in the NETSCAPE buffering sub-block.
+    pub loop_buffer_size: Option<u32>,
+    /// Convert ANIMEXTS1.0 to NETSCAPE2.0 and drop duplicate loop extensions.
+    pub normalize_loop: bool,
+    /// Delay for all frames (centiseconds).
+    pub delay_all: Option<u16>,
+    /// Per-frame delay overrides (centiseconds), keyed by 0-based frame index.
+    pub delays: Option<HashMap<usize, u16>>,
+    /// Delay changes for frames picked by selectors, applied in order after
+    /// `delay_all` and `delays`; each one starts from the result of the last.
+    pub frame_delays: Vec<FrameDelay>,
+    /// Playback speed factor (2.0 plays twice as fast, 0.5 at half speed),
+    /// applied to every delay after the other delay changes.
+    pub speed: Option<f64>,
+    /// Give every frame the same timing for this frame rate, replacing the
+    /// delays set above (before `speed` and `duration`).
+    pub fps: Option<f64>,
+    /// Scale every delay proportionally so the animation lasts this long,
+    /// applied after every other delay change.
+    pub duration: Option<Duration>,
+    /// Replace all comments with this one.
+    pub comment: Option<String>,
+    /// Comments to add after the existing ones.
+    pub append_comments: Vec<String>,
+    /// Remove every comment.
+    pub clear_comments: bool,
+    /// Replace (or add) the XMP packet with this XML.
+    pub xmp: Option<String>,
+    /// Remove every XMP packet.
+    pub strip_xmp: bool,
+    /// Replace (or add) the embedded ICC color profile.
+    pub icc_profile: Option<Vec<u8>>,
+    /// Remove the embedded ICC color profile.
+    pub strip_icc: bool,
+    /// Check the result before returning it: every frame must render the same,
+    /// requested values must be in place and nothing else may change.
+    pub verify: bool,
+}
+
+impl ModifyOptions {
+    /// True if the options would not change anything.
+    pub fn is_empty(&self) -> bool {
+        self.loop_count.is_none()
+            && !self.remove_loop
+            && self.loop_buffer_size.is_none()
+            && !self.normalize_loop
+            && self.delay_all.is_none()
+            && self.delays.as_ref().is_none_or(|m| m.is_empty())
+            && self.frame_delays.is_empty()
+            && self.speed.is_none()
+            && self.fps.is_none()
+            && self.duration.is_none()
+            && self.comment.is_none()
+            && self.append_comments.is_empty()
+            && !self.clear_comments
+            && self.xmp.is_none()
+            && !self.strip_xmp
+            && self.icc_profile.is_none()
+            && !self.strip_icc
+    }
+}
+
+/// A non-image block that `mod` carries across from input to output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BlockSummary {
+    /// Block kind, e.g. `comment`, `application` or `plain_text`.
+    pub kind: String,
+    /// Application identifier or extension label, when there is one.
+    pub label: Option<String>,
+    /// Offset of the block in the input file.
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// How serious a lint finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Worth knowing, but harmless.
+    Info,
+    /// Most decoders cope, but the file bends the spec or relies on decoder leniency.
+    Warning,
+    /// The file violates the spec in a way decoders are likely to reject or misrender.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A single problem found by `lint`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintFinding {
+    /// Stable identifier, e.g. `"missing-trailer"`.
+    pub code: &'static str,
+    pub severity: Severity,
+    /// Offset of the offending block or byte.
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Every finding of a `lint` run, in file order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.errors > 0
+    }
+}
+
+/// A single change made by `repair`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepairFix {
+    /// Offset in the input file the fix applies to.
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Outcome of a `repair` run.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RepairReport {
+    /// File the result was written to; `None` when it was returned in memory.
+    pub output: Option<PathBuf>,
+    pub original_size: usize,
+    pub repaired_size: usize,
+    /// Frames in the repaired file.
+    pub frames: usize,
+    /// Every change made, in file order; empty if the file was already valid.
+    pub fixes: Vec<RepairFix>,
+}
+
+/// One structural element of a GIF file, as listed by `blocks`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BlockDump {
+    pub offset: usize,
+    pub len: usize,
+    /// Element type, e.g. `"graphic_control"` or `"image"`.
+    pub kind: String,
+    /// Human-readable element name.
+    pub name: String,
+    /// Decoded fields, in display order.
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    /// Raw bytes of the element as lowercase hex, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hex: Option<String>,
+}
+
+/// A kind of non-essential block that `strip` can remove.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StripCategory {
+    /// NETSCAPE2.0 / ANIMEXTS1.0 loop extensions.
+    Loop,
+    /// Comment Extensions.
+    Comments,
+    /// The XMP packet.
+    Xmp,
+    /// The ICC color profile.
+    Icc,
+    /// Any other Application Extension.
+    Applications,
+    /// Plain Text Extensions, with the Graphic Control Extension that precedes them.
+    PlainText,
+    /// Extensions with an unknown label.
+    Extensions,
+    /// Bytes after the trailer.
+    Trailing,
+}
+
+impl StripCategory {
+    pub const ALL: [StripCategory; 8] = [
+        StripCategory::Loop,
+        StripCategory::Comments,
+        StripCategory::Xmp,
+        StripCategory::Icc,
+        StripCategory::Applications,
+        StripCategory::PlainText,
+        StripCategory::Extensions,
+        StripCategory::Trailing,
+    ];
+
+    /// Name used on the command line and in reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StripCategory::Loop => "loop",
+            StripCategory::Comments => "comments",
+            StripCategory::Xmp => "xmp",
+            StripCategory::Icc => "icc",
+            StripCategory::Applications => "applications",
+            StripCategory::PlainText => "plain-text",
+            StripCategory::Extensions => "extensions",
+            StripCategory::Trailing => "trailing",
+        }
+    }
+}
+
+impl std::str::FromStr for StripCategory {
+    type Err = GifError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StripCategory::ALL
+            .into_iter()
+            .find(|c| c.as_str() == s.trim())
+            .ok_or_else(|| {
+                let names: Vec<_> = StripCategory::ALL.iter().map(|c| c.as_str()).collect();
+                GifError::invalid_spec(
+                    s,
+                    format!("Unknown category, expected one of {}", names.join(", ")),
+                )
+            })
+    }
+}
+
+/// Which blocks `strip` keeps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StripOptions {
+    /// Categories to keep; everything else that is not needed to render is removed.
+    pub keep: Vec<StripCategory>,
+}
+
+impl Default for StripOptions {
+    /// Keeps only the loop extension, so animations still play as intended.
+    fn default() -> Self {
+        StripOptions {
+            keep: vec![StripCategory::Loop],
+        }
+    }
+}
+
+/// What `strip` removed from one category.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StrippedCategory {
+    pub category: StripCategory,
+    pub blocks: usize,
+    pub bytes: usize,
+}
+
+/// Outcome of a `strip` run.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct StripReport {
+    /// File the result was written to; `None` when it was returned in memory.
+    pub output: Option<PathBuf>,
+    pub original_size: usize,
+    pub stripped_size: usize,
+    /// One entry per category that was not kept, in [`StripCategory::ALL`] order.
+    pub removed: Vec<StrippedCategory>,
+}
+
+/// Outcome of a `mod` run.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ModifyReport {
+    /// File the result was written to; `None` when it was returned in memory.
+    pub output: Option<PathBuf>,
+    /// Set when the output was checked with [`ModifyOptions::verify`].
+    pub verification: Option<VerifyReport>,
+    /// Set when [`ModifyOptions::fps`] or [`ModifyOptions::duration`] was used.
+    pub timing: Option<TimingReport>,
+    /// Comment, application, plain text and unknown extensions found in the output.
+    pub preserved: Vec<BlockSummary>,
+    /// Blocks from the input that did not survive the edit.
+    pub dropped: Vec<BlockSummary>,
+}
+
+/// How close `--fps` or `--duration` got to the requested timing, which can
+/// only be matched to the nearest centisecond.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimingReport {
+    pub frame_count: usize,
+    pub old_duration_cs: u32,
+    pub new_duration_cs: u32,
+    /// The exact total that was asked for, in centiseconds.
+    pub requested_duration_cs: f64,
+}
+
+impl TimingReport {
+    /// Average frame rate of the output, or None if it has no duration.
+    pub fn fps(&self) -> Option<f64> {
+        (self.new_duration_cs > 0)
+            .then(|| self.frame_count as f64 * 100.0 / self.new_duration_cs as f64)
+    }
+}
+
+/// A frame whose delay `mod` would change.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DelayChange {
+    pub frame: usize,
+    /// Current delay in centiseconds (0 for a frame without a Graphic Control Extension).
+    pub old_cs: u16,
+    pub new_cs: u16,
+}
+
+/// How the total number of plays would change; `None` means a malformed loop extension.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoopChange {
+    pub old: Option<PlayCount>,
+    pub new: Option<PlayCount>,
+}
+
+/// A block that `mod` would rewrite, before and after.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BlockChange {
+    pub before: BlockSummary,
+    pub after: BlockSummary,
+}
+
+/// Everything a `mod` run would change, computed without writing anything.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModifyPlan {
+    pub original_size: usize,
+    pub output_size: usize,
+    /// Set when the number of plays changes.
+    pub loop_count: Option<LoopChange>,
+    /// Frames whose delay changes, in frame order.
+    pub delays: Vec<DelayChange>,
+    /// Set when [`ModifyOptions::fps`] or [`ModifyOptions::duration`] was used.
+    pub timing: Option<TimingReport>,
+    /// Blocks that only exist in the output; offsets refer to the output.
+    pub added: Vec<BlockSummary>,
+    /// Blocks that would be deleted; offsets refer to the input.
+    pub removed: Vec<BlockSummary>,
+    /// Blocks whose contents would change in place, such as a loop extension.
+    pub changed: Vec<BlockChange>,
+    /// Removed blocks that were not asked for (see [`ModifyReport::dropped`]).
+    pub dropped: Vec<BlockSummary>,
+}
+
+impl ModifyPlan {
+    /// Whether applying the modifications would leave the file byte-for-byte unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.loop_count.is_none()
+            && self.delays.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.original_size == self.output_size
+    }
+}
+
+/// Options for comparing two GIFs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffOptions {
+    /// Also render every frame of both files and compare the pixels.
+    pub compare_pixels: bool,
+}
+
+/// One field that differs between two GIFs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Difference {
+    /// What differs, e.g. `loop_count`, `global_palette` or `frames[3].delay_cs`.
+    pub path: String,
+    pub left: serde_json::Value,
+    pub right: serde_json::Value,
+    /// Extra detail when the values alone do not show the change.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Result of comparing two GIFs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GifDiff {
+    pub differences: Vec<Difference>,
+    /// Whether rendered frames were compared as well as metadata.
+    pub pixels_compared: bool,
+}
+
+impl GifDiff {
+    pub fn is_identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Result of checking a `mod` output against its input and the requested changes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerifyReport {
+    /// Frames whose rendered pixels were compared.
+    pub frames_compared: usize,
+    /// Requested values that are missing and changes that were not requested.
+    pub problems: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
 }
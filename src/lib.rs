@@ -1,10 +1,16 @@
+pub mod blocks;
 pub mod commands;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 pub mod gifmeta_structs;
 pub mod utils;
 
+pub use gifmeta_structs::GifError;
+
 /// Prints detailed metadata about the provided GIF file.
 ///
 /// This includes dimensions, frame count, duration, and loop count.
@@ -19,41 +25,386 @@ pub mod utils;
 /// get_metadata(&std::path::PathBuf::from("tests/testdata/loop/1.gif"), false);
 /// ```
 pub fn get_metadata(
-    path: &PathBuf,
+    path: &Path,
     show_frames: bool,
-) -> Result<gifmeta_structs::GifMetadata, String> {
-    match commands::info::get_metadata(path, show_frames) {
-        Ok(meta) => Ok(meta),
-        Err(e) => Err(e),
-    }
+) -> Result<gifmeta_structs::GifMetadata, GifError> {
+    commands::info::get_metadata(path, show_frames)
+}
+
+/// Like [`get_metadata`], but reports what can be salvaged from a truncated or
+/// malformed file instead of failing.
+///
+/// The file is repaired in memory (see [`repair_gif`]) when it cannot be read
+/// as-is, and `warnings` in the result describes the damage.
+///
+/// # Example
+/// ```
+/// use gifmeta::get_metadata_tolerant;
+/// let meta = get_metadata_tolerant(std::path::Path::new("tests/testdata/loop/2.gif"), false).unwrap();
+/// assert!(meta.warnings.is_empty());
+/// ```
+pub fn get_metadata_tolerant(
+    path: &Path,
+    show_frames: bool,
+) -> Result<gifmeta_structs::GifMetadata, GifError> {
+    commands::info::get_metadata_tolerant(path, show_frames)
 }
 
+/// Like [`get_metadata`], reading the GIF from memory instead of a file.
+///
+/// # Example
+/// ```
+/// use gifmeta::get_metadata_from_bytes;
+/// let data = std::fs::read("tests/testdata/loop/2.gif").unwrap();
+/// let meta = get_metadata_from_bytes(&data, false).unwrap();
+/// assert_eq!((meta.width, meta.height), (640, 360));
+/// ```
+pub fn get_metadata_from_bytes(
+    data: &[u8],
+    show_frames: bool,
+) -> Result<gifmeta_structs::GifMetadata, GifError> {
+    commands::info::metadata_from_bytes(data.to_vec(), show_frames)
+}
+
+/// Like [`get_metadata_tolerant`], reading the GIF from memory instead of a file.
+pub fn get_metadata_tolerant_from_bytes(
+    data: &[u8],
+    show_frames: bool,
+) -> Result<gifmeta_structs::GifMetadata, GifError> {
+    commands::info::tolerant_metadata_from_bytes(data.to_vec(), show_frames)
+}
+
+/// Salvages a truncated or slightly malformed GIF into a valid file.
+///
+/// Complete frames are kept as-is, a frame cut off mid-stream keeps its
+/// complete rows, the trailer is appended and out-of-range header fields are
+/// fixed. The report lists every fix.
+///
+/// If `output` is None, the result is written to a new file in the system
+/// temp directory and the input is left alone.
+///
+/// # Example
+/// ```
+/// use gifmeta::repair_gif;
+/// let report = repair_gif(
+///     std::path::Path::new("tests/testdata/loop/2.gif"),
+///     Some("tests/output/2-repaired.gif".into()),
+/// )
+/// .unwrap();
+/// assert!(report.fixes.is_empty());
+/// ```
+pub fn repair_gif(
+    input: &Path,
+    output: Option<PathBuf>,
+) -> Result<gifmeta_structs::RepairReport, GifError> {
+    commands::repair::repair_file(input, output)
+}
+
+/// Like [`repair_gif`], working on a GIF held in memory.
+///
+/// Returns the repaired bytes; the report's `output` is `None`.
+pub fn repair_gif_from_bytes(
+    data: &[u8],
+) -> Result<(Vec<u8>, gifmeta_structs::RepairReport), GifError> {
+    commands::repair::repair_bytes(data.to_vec())
+}
+
+/// Applies loop count and frame delay changes to a GIF file.
+///
+/// Comments, application extensions and other non-image blocks are carried
+/// across unchanged; the returned report lists them along with anything dropped.
 pub fn mod_gif(
     input: &PathBuf,
     output: Option<PathBuf>,
     loop_count: Option<u16>,
     delay_all: Option<u16>,
     delays: Option<HashMap<usize, u16>>,
-) -> Result<(), String> {
-    if loop_count.is_none() && delay_all.is_none() && delays.as_ref().map_or(true, |m| m.is_empty())
-    {
+) -> Result<gifmeta_structs::ModifyReport, GifError> {
+    let options = gifmeta_structs::ModifyOptions {
+        loop_count,
+        delay_all,
+        delays,
+        ..Default::default()
+    };
+    mod_gif_with_options(input, output, &options)
+}
+
+/// Applies every change described by `options` to a GIF file.
+///
+/// This is the full form of [`mod_gif`], covering options such as removing
+/// the loop extension.
+///
+/// If `output` is None, the result is written to a new file in the system
+/// temp directory and the input is left alone.
+///
+/// # Example
+/// ```
+/// use gifmeta::{gifmeta_structs::ModifyOptions, mod_gif_with_options};
+/// let options = ModifyOptions { remove_loop: true, ..Default::default() };
+/// mod_gif_with_options(
+///     &"tests/testdata/loop/2frame-soft.gif".into(),
+///     Some("tests/output/2frame-soft-once.gif".into()),
+///     &options,
+/// );
+/// ```
+pub fn mod_gif_with_options(
+    input: &PathBuf,
+    output: Option<PathBuf>,
+    options: &gifmeta_structs::ModifyOptions,
+) -> Result<gifmeta_structs::ModifyReport, GifError> {
+    if options.is_empty() {
         eprintln!("⚠️  No modifications specified.");
-        eprintln!("   Use at least one of: --loop, --delay, or --delays");
-        return Err("No modification parameters provided.".into());
+        eprintln!("   Use at least one of: --loop-count, --no-loop, --delay, --delays, --speed, --slow, --fps, --duration, or a comment, XMP or ICC option");
+        return Err(GifError::NoModifications);
     }
 
-    match commands::modify::apply_modifications(input, loop_count, delay_all, delays, output) {
-        Ok(_) => {
+    match commands::modify::apply_modifications(input, options, output) {
+        Ok(report) => {
             println!("File modified.");
-            Ok(())
+            Ok(report)
         }
         Err(e) => {
-            eprintln!("Failed to extract loop count: {}", e);
+            eprintln!("Failed to modify GIF: {}", e);
             Err(e)
         }
     }
 }
 
+/// Like [`mod_gif_with_options`], working on a GIF held in memory.
+///
+/// Returns the modified bytes; the report's `output` is `None`. Nothing is
+/// printed to stdout, so the result can be piped straight on.
+///
+/// # Example
+/// ```
+/// use gifmeta::{get_metadata_from_bytes, gifmeta_structs::ModifyOptions, mod_gif_from_bytes};
+/// let data = std::fs::read("tests/testdata/loop/2.gif").unwrap();
+/// let options = ModifyOptions { loop_count: Some(0), ..Default::default() };
+/// let (modified, _report) = mod_gif_from_bytes(&data, &options).unwrap();
+/// assert_eq!(get_metadata_from_bytes(&modified, false).unwrap().repeat_count, Some(0));
+/// ```
+pub fn mod_gif_from_bytes(
+    data: &[u8],
+    options: &gifmeta_structs::ModifyOptions,
+) -> Result<(Vec<u8>, gifmeta_structs::ModifyReport), GifError> {
+    if options.is_empty() {
+        return Err(GifError::NoModifications);
+    }
+    commands::modify::modify_bytes(data.to_vec(), options)
+}
+
+/// Checks that `output` is `input` with exactly the changes in `options`.
+///
+/// Every frame must render to the same pixels, the requested loop count,
+/// delays, comments, XMP and ICC values must be in place, and no other
+/// metadata may differ. Setting [`ModifyOptions::verify`](gifmeta_structs::ModifyOptions::verify)
+/// runs the same check before `mod` returns or writes anything.
+///
+/// # Example
+/// ```
+/// use gifmeta::{gifmeta_structs::ModifyOptions, mod_gif_from_bytes, verify_mod_gif_from_bytes};
+/// let data = std::fs::read("tests/testdata/loop/2.gif").unwrap();
+/// let options = ModifyOptions { delay_all: Some(8), ..Default::default() };
+/// let (output, _) = mod_gif_from_bytes(&data, &options).unwrap();
+/// assert!(verify_mod_gif_from_bytes(&data, &output, &options).unwrap().is_ok());
+/// ```
+pub fn verify_mod_gif(
+    input: &Path,
+    output: &Path,
+    options: &gifmeta_structs::ModifyOptions,
+) -> Result<gifmeta_structs::VerifyReport, GifError> {
+    let read = |path: &Path| std::fs::read(path).map_err(|e| GifError::io(path, e));
+    commands::verify::verify_modifications(&read(input)?, &read(output)?, options)
+}
+
+/// Like [`verify_mod_gif`], checking GIFs held in memory.
+pub fn verify_mod_gif_from_bytes(
+    input: &[u8],
+    output: &[u8],
+    options: &gifmeta_structs::ModifyOptions,
+) -> Result<gifmeta_structs::VerifyReport, GifError> {
+    commands::verify::verify_modifications(input, output, options)
+}
+
+/// Works out what [`mod_gif_with_options`] would change, without writing anything.
+///
+/// The plan lists old → new delays per frame, the old → new number of plays,
+/// blocks added, removed or rewritten, and the resulting file size.
+///
+/// # Example
+/// ```
+/// use gifmeta::{gifmeta_structs::ModifyOptions, plan_mod_gif};
+/// let options = ModifyOptions { delay_all: Some(5), ..Default::default() };
+/// let plan = plan_mod_gif(std::path::Path::new("tests/testdata/loop/2.gif"), &options).unwrap();
+/// assert_eq!(plan.delays.len(), 2);
+/// assert_eq!(plan.output_size, plan.original_size);
+/// ```
+pub fn plan_mod_gif(
+    input: &Path,
+    options: &gifmeta_structs::ModifyOptions,
+) -> Result<gifmeta_structs::ModifyPlan, GifError> {
+    let data = std::fs::read(input).map_err(|e| GifError::io(input, e))?;
+    plan_mod_gif_from_bytes(&data, options)
+}
+
+/// Like [`plan_mod_gif`], reading the GIF from memory instead of a file.
+pub fn plan_mod_gif_from_bytes(
+    data: &[u8],
+    options: &gifmeta_structs::ModifyOptions,
+) -> Result<gifmeta_structs::ModifyPlan, GifError> {
+    if options.is_empty() {
+        return Err(GifError::NoModifications);
+    }
+    commands::modify::plan_modifications(data.to_vec(), options)
+}
+
+/// Runs an in-memory edit on a file and atomically replaces it with the result.
+///
+/// `edit` is one of the `*_from_bytes` functions (or anything with the same
+/// shape). The result goes to a temp file next to `path`, is synced to disk
+/// and renamed over the original, keeping its permissions; `options` can keep
+/// a backup and the original modification time. The file is left untouched if
+/// the edit fails.
+///
+/// # Example
+/// ```
+/// use gifmeta::gifmeta_structs::{InPlaceOptions, ModifyOptions};
+/// std::fs::create_dir_all("tests/output").unwrap();
+/// std::fs::copy("tests/testdata/loop/2.gif", "tests/output/2-in-place.gif").unwrap();
+/// let options = ModifyOptions { loop_count: Some(0), ..Default::default() };
+/// let in_place = InPlaceOptions { backup_suffix: Some(".bak".into()), ..Default::default() };
+/// gifmeta::edit_in_place("tests/output/2-in-place.gif".as_ref(), &in_place, |data| {
+///     gifmeta::mod_gif_from_bytes(data, &options)
+/// })
+/// .unwrap();
+/// assert!(std::path::Path::new("tests/output/2-in-place.gif.bak").exists());
+/// ```
+pub fn edit_in_place<R>(
+    path: &Path,
+    options: &gifmeta_structs::InPlaceOptions,
+    edit: impl FnOnce(&[u8]) -> Result<(Vec<u8>, R), GifError>,
+) -> Result<R, GifError> {
+    utils::in_place::edit_in_place(path, options, edit)
+}
+
+/// Removes comments, XMP, ICC profiles, unknown extensions, plain text and
+/// trailing bytes from a GIF file, keeping the categories in `options.keep`.
+///
+/// Image data is never re-encoded; the report lists how many blocks and bytes
+/// each removed category accounted for.
+///
+/// If `output` is None, the result is written to a new file in the system
+/// temp directory and the input is left alone.
+///
+/// # Example
+/// ```
+/// use gifmeta::{gifmeta_structs::StripOptions, strip_gif};
+/// let report = strip_gif(
+///     std::path::Path::new("tests/testdata/loop/2.gif"),
+///     Some("tests/output/2-stripped.gif".into()),
+///     &StripOptions::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(report.original_size - report.stripped_size, 21); // the GIMP comment
+/// ```
+pub fn strip_gif(
+    input: &Path,
+    output: Option<PathBuf>,
+    options: &gifmeta_structs::StripOptions,
+) -> Result<gifmeta_structs::StripReport, GifError> {
+    commands::strip::strip_metadata(input, options, output)
+}
+
+/// Like [`strip_gif`], working on a GIF held in memory.
+///
+/// Returns the stripped bytes; the report's `output` is `None`.
+pub fn strip_gif_from_bytes(
+    data: &[u8],
+    options: &gifmeta_structs::StripOptions,
+) -> Result<(Vec<u8>, gifmeta_structs::StripReport), GifError> {
+    commands::strip::strip_bytes(data.to_vec(), options)
+}
+
+/// Compares two GIF files and lists every difference.
+///
+/// Covers dimensions, frame count and duration, loop settings, per-frame
+/// delay, disposal, transparency, position and plain text, global and local
+/// palettes, comments and extensions. With `compare_pixels`, every frame of
+/// both files is rendered and compared as well.
+///
+/// # Example
+/// ```
+/// use gifmeta::{diff_gifs, gifmeta_structs::DiffOptions};
+/// let path = std::path::Path::new("tests/testdata/loop/2.gif");
+/// let diff = diff_gifs(path, path, &DiffOptions { compare_pixels: true }).unwrap();
+/// assert!(diff.is_identical());
+/// ```
+pub fn diff_gifs(
+    left: &Path,
+    right: &Path,
+    options: &gifmeta_structs::DiffOptions,
+) -> Result<gifmeta_structs::GifDiff, GifError> {
+    let read = |path: &Path| std::fs::read(path).map_err(|e| GifError::io(path, e));
+    commands::diff::diff_data(read(left)?, read(right)?, options)
+}
+
+/// Like [`diff_gifs`], comparing two GIFs held in memory.
+pub fn diff_gifs_from_bytes(
+    left: &[u8],
+    right: &[u8],
+    options: &gifmeta_structs::DiffOptions,
+) -> Result<gifmeta_structs::GifDiff, GifError> {
+    commands::diff::diff_data(left.to_vec(), right.to_vec(), options)
+}
+
+/// Lists every structural element of a GIF file in order, with offset, length,
+/// type and decoded fields, and optionally the raw bytes as hex.
+///
+/// # Example
+/// ```
+/// use gifmeta::dump_blocks;
+/// let blocks = dump_blocks(std::path::Path::new("tests/testdata/loop/2.gif"), false).unwrap();
+/// let netscape = blocks.iter().find(|b| b.kind == "application").unwrap();
+/// assert_eq!(netscape.offset, 781);
+/// assert_eq!(netscape.fields["repeat"], 5);
+/// ```
+pub fn dump_blocks(path: &Path, hex: bool) -> Result<Vec<gifmeta_structs::BlockDump>, GifError> {
+    Ok(commands::dump::dump_blocks(&blocks::read_file(path)?, hex))
+}
+
+/// Like [`dump_blocks`], reading the GIF from memory instead of a file.
+pub fn dump_blocks_from_bytes(
+    data: &[u8],
+    hex: bool,
+) -> Result<Vec<gifmeta_structs::BlockDump>, GifError> {
+    Ok(commands::dump::dump_blocks(
+        &blocks::parse(data.to_vec())?,
+        hex,
+    ))
+}
+
+/// Checks a GIF file against the GIF89a spec and common decoder expectations.
+///
+/// Spec problems are reported as findings, each with a code, severity and byte
+/// offset; only failing to read the file is an `Err`. A file that cannot be
+/// parsed yields a single `parse-error` finding.
+///
+/// # Example
+/// ```
+/// use gifmeta::lint_gif;
+/// let report = lint_gif(std::path::Path::new("tests/testdata/loop/2.gif")).unwrap();
+/// assert!(!report.has_errors());
+/// ```
+pub fn lint_gif(path: &Path) -> Result<gifmeta_structs::LintReport, GifError> {
+    let data = std::fs::read(path).map_err(|e| GifError::io(path, e))?;
+    Ok(commands::lint::lint_data(data))
+}
+
+/// Like [`lint_gif`], checking a GIF held in memory.
+pub fn lint_gif_from_bytes(data: &[u8]) -> gifmeta_structs::LintReport {
+    commands::lint::lint_data(data.to_vec())
+}
+
 /// Prints the loop count of the provided GIF file.
 ///
 /// # Arguments
@@ -65,7 +416,7 @@ pub fn mod_gif(
 /// use gifmeta::get_loop_count;
 /// get_loop_count(&std::path::PathBuf::from("tests/testdata/loop/2.gif"));
 /// ```
-pub fn get_loop_count(path: &PathBuf) -> Result<u16, String> {
+pub fn get_loop_count(path: &PathBuf) -> Result<u16, GifError> {
     match utils::loop_count::extract_loop_count(path) {
         Ok(count) => {
             println!("Loop count: {}", count);
@@ -86,14 +437,23 @@ pub fn get_loop_count(path: &PathBuf) -> Result<u16, String> {
 ///
 /// * `path` - Path to the input `.gif` file.
 /// * `delay` - Desired delay in centiseconds (e.g., `10` = 100ms).
-/// * `output` - Optional path for the output file. If `None`, overwrites original.
+/// * `output` - Optional path for the output file. If `None`, the original is
+///   replaced atomically.
 ///
 /// # Example
 /// ```
 /// use gifmeta::set_frame_delay;
-/// set_frame_delay(&"tests/testdata/1.gif".into(), 10, None);
+/// set_frame_delay(
+///     &"tests/testdata/loop/2.gif".into(),
+///     10,
+///     Some("tests/output/2-delay-10.gif".into()),
+/// );
 /// ```
-pub fn set_frame_delay(path: &PathBuf, delay: u16, output: Option<PathBuf>) -> Result<u16, String> {
+pub fn set_frame_delay(
+    path: &PathBuf,
+    delay: u16,
+    output: Option<PathBuf>,
+) -> Result<u16, GifError> {
     let output_clone = output.clone();
     println!(
         "(stub) Setting delay {} for: {:?} → {:?}",
@@ -119,14 +479,23 @@ pub fn set_frame_delay(path: &PathBuf, delay: u16, output: Option<PathBuf>) -> R
 ///
 /// * `path` - Path to the input `.gif` file.
 /// * `count` - Number of loops (e.g., `0` = infinite).
-/// * `output` - Optional path for the output file. If `None`, overwrites original.
+/// * `output` - Optional path for the output file. If `None`, the original is
+///   replaced atomically.
 ///
 /// # Example
 /// ```
 /// use gifmeta::set_loop_count;
-/// set_loop_count(&"tests/testdata/loop/2.gif".into(), 3, None);
+/// set_loop_count(
+///     &"tests/testdata/loop/2.gif".into(),
+///     3,
+///     Some("tests/output/2-loop-3.gif".into()),
+/// );
 /// ```
-pub fn set_loop_count(path: &PathBuf, count: u16, output: Option<PathBuf>) -> Result<u16, String> {
+pub fn set_loop_count(
+    path: &PathBuf,
+    count: u16,
+    output: Option<PathBuf>,
+) -> Result<u16, GifError> {
     let output_clone = output.clone();
     match utils::loop_count::set_loop_count(path, count, output) {
         Ok(_) => {
@@ -134,12 +503,71 @@ pub fn set_loop_count(path: &PathBuf, count: u16, output: Option<PathBuf>) -> Re
             Ok(count)
         }
         Err(e) => {
-            eprintln!("Failed to extract loop count: {}", e);
+            eprintln!("Failed to set loop count: {}", e);
             Err(e)
         }
     }
 }
 
+/// Lists the Comment Extensions of a GIF file in file order.
+///
+/// # Example
+/// ```
+/// use gifmeta::get_comments;
+/// let comments = get_comments(std::path::Path::new("tests/testdata/loop/2.gif")).unwrap();
+/// assert_eq!(comments[0].text, "Created with GIMP");
+/// ```
+pub fn get_comments(path: &Path) -> Result<Vec<gifmeta_structs::CommentMeta>, GifError> {
+    Ok(utils::comments::read_comments(&blocks::read_file(path)?))
+}
+
+/// Extracts the XMP metadata packet of a GIF file as XML, if it has one.
+///
+/// # Example
+/// ```
+/// use gifmeta::get_xmp;
+/// let xmp = get_xmp(std::path::Path::new("tests/testdata/loop/2.gif")).unwrap();
+/// assert!(xmp.is_none());
+/// ```
+pub fn get_xmp(path: &Path) -> Result<Option<String>, GifError> {
+    utils::xmp::read_xmp(&blocks::read_file(path)?)
+}
+
+/// Like [`get_xmp`], reading the GIF from memory instead of a file.
+pub fn get_xmp_from_bytes(data: &[u8]) -> Result<Option<String>, GifError> {
+    utils::xmp::read_xmp(&blocks::parse(data.to_vec())?)
+}
+
+/// Extracts the embedded ICC color profile of a GIF file, if it has one.
+///
+/// # Example
+/// ```
+/// use gifmeta::get_icc_profile;
+/// let profile = get_icc_profile(std::path::Path::new("tests/testdata/loop/2.gif")).unwrap();
+/// assert!(profile.is_none());
+/// ```
+pub fn get_icc_profile(path: &Path) -> Result<Option<Vec<u8>>, GifError> {
+    Ok(utils::icc::read_icc(&blocks::read_file(path)?))
+}
+
+/// Like [`get_icc_profile`], reading the GIF from memory instead of a file.
+pub fn get_icc_profile_from_bytes(data: &[u8]) -> Result<Option<Vec<u8>>, GifError> {
+    Ok(utils::icc::read_icc(&blocks::parse(data.to_vec())?))
+}
+
+/// Removes the loop extension from a GIF file so it plays exactly once.
+///
+/// Both `NETSCAPE2.0` and `ANIMEXTS1.0` blocks are deleted; nothing else changes.
+///
+/// # Arguments
+///
+/// * `path` - Path to the input `.gif` file.
+/// * `output` - Optional path for the output file. If `None`, the original is
+///   replaced atomically.
+pub fn remove_loop_count(path: &PathBuf, output: Option<PathBuf>) -> Result<(), GifError> {
+    utils::loop_count::remove_loop_count(path, output)
+}
+
 /// Displays the delay (in centiseconds) of each frame in the GIF.
 ///
 /// Useful for inspecting frame timing before editing or modifying.
@@ -175,7 +603,7 @@ pub fn show_frame_delays(path: &PathBuf) {
 /// * `frame_numbers` - A list of 0-based frame indices to modify
 /// * `delay_values` - A list of matching delays in centiseconds
 /// * `output` - Optional path to save the modified `.gif`
-///             If `None`, will overwrite the original input file
+///   If `None`, will overwrite the original input file
 ///
 /// # Returns
 ///
@@ -217,7 +645,7 @@ pub fn set_selected_frame_delays(
 /// # Returns
 ///
 /// * `Ok(Vec<u8>)` - PNG-encoded image data of the frame
-/// * `Err(String)` - Error message if the file cannot be read, decoded, or the frame is out of bounds
+/// * `Err(GifError)` - If the file cannot be read, decoded, or the frame is out of bounds
 ///
 /// # Example
 ///
@@ -226,6 +654,48 @@ pub fn set_selected_frame_delays(
 /// let png_data = get_frame_image("tests/testdata/loop/2.gif".into(), 0).unwrap();
 /// std::fs::write("tests/output/frame0.png", &png_data).unwrap();
 /// ```
-pub fn get_frame_image(path: String, frame: usize) -> Result<Vec<u8>, String> {
+pub fn get_frame_image(path: String, frame: usize) -> Result<Vec<u8>, GifError> {
     utils::extract_frame_as_png::extract_frame_as_png(&path, frame)
 }
+
+/// Extracts a single frame like [`get_frame_image`], with rendering options.
+///
+/// With `render_plain_text`, Plain Text Extensions are rasterized with a
+/// built-in monospace bitmap font over the frame they follow.
+///
+/// # Example
+///
+/// ```rust
+/// use gifmeta::get_frame_image_with_options;
+/// use gifmeta::gifmeta_structs::PreviewOptions;
+/// let options = PreviewOptions { render_plain_text: true };
+/// let png_data = get_frame_image_with_options("tests/testdata/loop/2.gif".into(), 0, &options).unwrap();
+/// assert!(!png_data.is_empty());
+/// ```
+pub fn get_frame_image_with_options(
+    path: String,
+    frame: usize,
+    options: &gifmeta_structs::PreviewOptions,
+) -> Result<Vec<u8>, GifError> {
+    utils::extract_frame_as_png::extract_frame_as_png_with_options(&path, frame, options)
+}
+
+/// Extracts a single frame like [`get_frame_image_with_options`], reading the
+/// GIF from memory instead of a file.
+///
+/// # Example
+///
+/// ```rust
+/// use gifmeta::get_frame_image_from_bytes;
+/// use gifmeta::gifmeta_structs::PreviewOptions;
+/// let data = std::fs::read("tests/testdata/loop/2.gif").unwrap();
+/// let png_data = get_frame_image_from_bytes(&data, 1, &PreviewOptions::default()).unwrap();
+/// assert!(png_data.starts_with(b"\x89PNG"));
+/// ```
+pub fn get_frame_image_from_bytes(
+    data: &[u8],
+    frame: usize,
+    options: &gifmeta_structs::PreviewOptions,
+) -> Result<Vec<u8>, GifError> {
+    utils::extract_frame_as_png::frame_png_from_bytes(data, frame, options)
+}
@@ -1,7 +1,365 @@
 use base64::engine::Engine;
-use clap::{Parser, Subcommand};
-use std::path::PathBuf; // Import the Engine trait for encode()
-pub mod utils;
+use clap::{Args, Parser, Subcommand};
+use gifmeta::commands::diff;
+use gifmeta::gifmeta_structs::{
+    BlockSummary, DelayOp, DiffOptions, FrameDelay, FrameSelector, GifDiff, InPlaceOptions,
+    ModifyOptions, ModifyPlan, PlayCount, PreviewOptions, StripCategory, StripOptions,
+    TimingReport,
+};
+use gifmeta::{utils, GifError};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf}; // Import the Engine trait for encode()
+
+/// Path that stands for stdin as an input and stdout as an output.
+const STDIO: &str = "-";
+
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO
+}
+
+/// Name of an input for messages.
+fn input_name(path: &Path) -> String {
+    if is_stdio(path) {
+        "<stdin>".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// Name of an output for messages.
+fn output_name(path: &Path) -> String {
+    if is_stdio(path) {
+        "<stdout>".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> GifError {
+    GifError::Io {
+        path: (!is_stdio(path)).then(|| path.to_path_buf()),
+        source,
+    }
+}
+
+/// Reads a file, or all of stdin for `-`.
+fn read_input(path: &Path) -> Result<Vec<u8>, GifError> {
+    let io_error = |source| io_error(path, source);
+    if is_stdio(path) {
+        let mut data = Vec::new();
+        std::io::stdin().read_to_end(&mut data).map_err(io_error)?;
+        Ok(data)
+    } else {
+        std::fs::read(path).map_err(io_error)
+    }
+}
+
+/// Writes a file, or stdout for `-`.
+fn write_output(path: &Path, data: &[u8]) -> Result<(), GifError> {
+    if is_stdio(path) {
+        let mut stdout = std::io::stdout().lock();
+        stdout
+            .write_all(data)
+            .and_then(|_| stdout.flush())
+            .map_err(|e| io_error(path, e))
+    } else {
+        utils::in_place::write_atomic(path, data)
+    }
+}
+
+/// Writes like [`write_output`], reporting a failure and exiting with status 1.
+fn write_output_or_exit(path: &Path, data: &[u8]) {
+    if let Err(e) = write_output(path, data) {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Whether a command that rewrites the GIF sends it to stdout.
+/// Reading stdin without `--output` writes stdout.
+fn writes_stdout(input: &Path, output: &Option<PathBuf>) -> bool {
+    match output {
+        Some(path) => is_stdio(path),
+        None => is_stdio(input),
+    }
+}
+
+/// Prints a status line, moving it to stderr while stdout carries the GIF.
+macro_rules! status {
+    ($to_stderr:expr, $($arg:tt)*) => {
+        if $to_stderr {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// Runs an edit on the input and writes the result: over the input for
+/// `--in-place`, to `--output`, to stdout when reading stdin, and otherwise to
+/// a new `<uuid>.<kind>.gif` in the temp directory so the input is left alone.
+/// Prints `done` with where the result went.
+fn run_edit<R>(
+    input: &Path,
+    output: &Option<PathBuf>,
+    in_place: &InPlaceArgs,
+    kind: &str,
+    done: &str,
+    edit: impl FnOnce(&[u8]) -> Result<(Vec<u8>, R), GifError>,
+) -> Result<R, GifError> {
+    if in_place.in_place {
+        if is_stdio(input) {
+            eprintln!("❌ --in-place needs an input file, not stdin");
+            std::process::exit(2);
+        }
+        let options = in_place.options();
+        let report = gifmeta::edit_in_place(input, &options, edit)?;
+        println!("✅ {} → {} (in place)", done, input.display());
+        if let Some(suffix) = &options.backup_suffix {
+            let backup = utils::in_place::backup_path(input, suffix);
+            println!("💾 Original kept as {}", backup.display());
+        }
+        return Ok(report);
+    }
+
+    let destination = match output {
+        Some(path) => path.clone(),
+        None if is_stdio(input) => PathBuf::from(STDIO),
+        None => utils::in_place::temp_output(kind),
+    };
+    let data = read_input(input)?;
+    let (data, report) = edit(&data)?;
+    write_output(&destination, &data)?;
+    status!(
+        is_stdio(&destination),
+        "✅ {} → {}",
+        done,
+        output_name(&destination)
+    );
+    Ok(report)
+}
+
+/// Describes a number of plays the way `info` prints it.
+fn plays_text(plays: Option<PlayCount>) -> String {
+    match plays {
+        Some(PlayCount::Infinite) => "infinite".to_string(),
+        Some(PlayCount::Times(1)) => "plays once".to_string(),
+        Some(PlayCount::Times(n)) => format!("plays {} times", n),
+        None => "unknown (malformed loop extension)".to_string(),
+    }
+}
+
+fn block_text(block: &BlockSummary) -> String {
+    format!(
+        "{} at offset {} ({} bytes)",
+        block.label.as_deref().unwrap_or(&block.kind),
+        block.offset,
+        block.len
+    )
+}
+
+/// Old → new duration, with the requested one when rounding to whole
+/// centiseconds missed it, and the frame rate achieved.
+fn timing_text(timing: &TimingReport) -> String {
+    let mut text = format!(
+        "{:.2} s → {:.2} s",
+        timing.old_duration_cs as f64 / 100.0,
+        timing.new_duration_cs as f64 / 100.0
+    );
+    if (timing.requested_duration_cs - timing.new_duration_cs as f64).abs() > 1e-6 {
+        text += &format!(" (requested {:.3} s)", timing.requested_duration_cs / 100.0);
+    }
+    if let Some(fps) = timing.fps() {
+        text += &format!(", {:.2} fps", fps);
+    }
+    text
+}
+
+/// Prints a `mod --dry-run` plan.
+fn print_plan(input: &Path, plan: &ModifyPlan) {
+    println!("🔍 Dry run for {}: nothing written\n", input_name(input));
+    if plan.is_empty() {
+        println!("No changes");
+        return;
+    }
+    if let Some(change) = &plan.loop_count {
+        println!(
+            "🔄 Loop          : {} → {}",
+            plays_text(change.old),
+            plays_text(change.new)
+        );
+    }
+    for change in &plan.delays {
+        println!(
+            "⏱️ Frame {:>3}     : {:>4} cs → {:>4} cs",
+            change.frame, change.old_cs, change.new_cs
+        );
+    }
+    if let Some(timing) = &plan.timing {
+        println!("⏱️ Duration      : {}", timing_text(timing));
+    }
+    for block in &plan.removed {
+        println!("➖ Remove        : {}", block_text(block));
+    }
+    for block in &plan.added {
+        println!("➕ Add           : {}", block_text(block));
+    }
+    for change in &plan.changed {
+        println!(
+            "✏️ Rewrite       : {} → {} bytes",
+            block_text(&change.before),
+            change.after.len
+        );
+    }
+    for block in &plan.dropped {
+        println!("⚠️ Would drop    : {}", block_text(block));
+    }
+    println!(
+        "📦 Size          : {} → {} bytes ({:+})",
+        plan.original_size,
+        plan.output_size,
+        plan.output_size as i64 - plan.original_size as i64
+    );
+}
+
+/// Prints the result of `diff`.
+fn print_diff(left: &Path, right: &Path, diff: &GifDiff) {
+    if diff.is_identical() {
+        println!(
+            "✅ {} and {} are identical ({})",
+            input_name(left),
+            input_name(right),
+            if diff.pixels_compared {
+                "metadata and pixels"
+            } else {
+                "metadata"
+            }
+        );
+        return;
+    }
+    println!("--- {}\n+++ {}\n", input_name(left), input_name(right));
+    let width = diff
+        .differences
+        .iter()
+        .map(|d| d.path.len())
+        .max()
+        .unwrap_or(0);
+    for d in &diff.differences {
+        println!(
+            "{:<width$} : {} → {}{}",
+            d.path,
+            diff::value_text(&d.left),
+            diff::value_text(&d.right),
+            d.note
+                .as_ref()
+                .map(|n| format!(" ({})", n))
+                .unwrap_or_default(),
+            width = width
+        );
+    }
+    println!("\n{} difference(s)", diff.differences.len());
+}
+
+/// Flags for replacing the input file instead of writing a new one.
+#[derive(Args)]
+struct InPlaceArgs {
+    /// Replace the input file atomically instead of writing a new one
+    #[arg(long, conflicts_with = "output")]
+    in_place: bool,
+
+    /// With --in-place, keep the original as INPUT<SUFFIX> (default ".bak")
+    #[arg(
+        long,
+        value_name = "SUFFIX",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ".bak",
+        requires = "in_place"
+    )]
+    backup: Option<String>,
+
+    /// With --in-place, keep the original modification time
+    #[arg(long, requires = "in_place")]
+    preserve_mtime: bool,
+}
+
+impl InPlaceArgs {
+    fn options(&self) -> InPlaceOptions {
+        InPlaceOptions {
+            backup_suffix: self.backup.clone(),
+            preserve_mtime: self.preserve_mtime,
+        }
+    }
+}
+
+/// Flags that change frame timing.
+#[derive(Args)]
+struct TimingArgs {
+    /// Delay for all frames in centiseconds, or a change: +5, -2, *1.5, /2, or a clamp 2..10
+    #[arg(long, allow_hyphen_values = true)]
+    delay: Option<String>,
+
+    /// Per-frame delays as SELECTOR=DELAY, e.g. 1=10,5-9=20,10-=+8,0-20/2=*2,-1=200 or last=2..
+    #[arg(long, allow_hyphen_values = true)]
+    delays: Option<String>,
+
+    /// Play faster by this factor (1.5 = 50% faster), rescaling every delay
+    #[arg(long, value_name = "FACTOR")]
+    speed: Option<f64>,
+
+    /// Play slower by this factor (2 = half speed), rescaling every delay
+    #[arg(long, value_name = "FACTOR", conflicts_with = "speed")]
+    slow: Option<f64>,
+
+    /// Give every frame even timing for this frame rate (e.g. 25)
+    #[arg(long, conflicts_with_all = ["delay", "delays", "speed", "slow", "duration"])]
+    fps: Option<f64>,
+
+    /// Scale every delay so the animation lasts this long (e.g. 2.4s, 2400ms, 240cs)
+    #[arg(long, conflicts_with_all = ["speed", "slow"])]
+    duration: Option<String>,
+}
+
+impl TimingArgs {
+    /// Parses the flags into the delay fields of `options`.
+    fn apply(&self, options: &mut ModifyOptions) -> Result<(), GifError> {
+        let invalid = |value: f64, message: &str| GifError::InvalidSpec {
+            input: value.to_string(),
+            message: message.to_string(),
+        };
+        let positive = |value: &f64| value.is_finite() && *value > 0.0;
+        if let Some(factor) = self.speed.or(self.slow).filter(|f| !positive(f)) {
+            return Err(invalid(factor, "Speed factor must be a positive number"));
+        }
+        if let Some(fps) = self.fps.filter(|f| !positive(f)) {
+            return Err(invalid(fps, "Frame rate must be a positive number"));
+        }
+
+        let mut frame_delays = Vec::new();
+        match self.delay.as_deref().map(utils::parse_csv::parse_delay_op) {
+            Some(Ok(DelayOp::Set(delay))) => options.delay_all = Some(delay),
+            // A relative --delay is applied to every frame before --delays
+            Some(Ok(op)) => frame_delays.push(FrameDelay {
+                frames: FrameSelector::all(),
+                op,
+            }),
+            Some(Err(e)) => return Err(e),
+            None => {}
+        }
+        if let Some(delays) = &self.delays {
+            frame_delays.extend(utils::parse_csv::parse_frame_delays(delays)?);
+        }
+        options.frame_delays = frame_delays;
+        options.speed = self.speed.or(self.slow.map(|factor| 1.0 / factor));
+        options.fps = self.fps;
+        options.duration = self
+            .duration
+            .as_deref()
+            .map(utils::parse_csv::parse_duration)
+            .transpose()?;
+        Ok(())
+    }
+}
 
 #[derive(Parser)]
 #[command(name = "gifmeta", version, about = "Inspect and edit GIF metadata")]
@@ -14,7 +372,7 @@ struct Cli {
 enum Commands {
     /// Show metadata
     Info {
-        /// Path to the GIF file
+        /// Path to the GIF file ("-" reads stdin)
         #[arg(value_name = "INPUT")]
         path: PathBuf,
 
@@ -25,11 +383,15 @@ enum Commands {
         /// Get output as json
         #[arg(long)]
         json: bool, // ← Add this
+
+        /// Report what can be salvaged from a damaged file instead of failing
+        #[arg(long)]
+        tolerant: bool,
     },
 
     /// Modify GIF file
     Mod {
-        /// Path to the GIF file
+        /// Path to the GIF file ("-" reads stdin)
         #[arg(value_name = "INPUT")]
         input: PathBuf,
 
@@ -37,22 +399,173 @@ enum Commands {
         #[arg(long)]
         loop_count: Option<u16>,
 
-        /// Delay for all frame
+        /// Remove the loop extension entirely (the GIF plays once)
+        #[arg(long, conflicts_with = "loop_count")]
+        no_loop: bool,
+
+        /// Set the NETSCAPE buffering sub-block (buffer size in bytes)
+        #[arg(long, value_name = "BYTES", conflicts_with = "no_loop")]
+        loop_buffer: Option<u32>,
+
+        /// Rewrite ANIMEXTS1.0 as NETSCAPE2.0 and drop duplicate loop extensions
+        #[arg(long, conflicts_with = "no_loop")]
+        normalize_loop: bool,
+
+        #[command(flatten)]
+        timing: Box<TimingArgs>,
+
+        /// Replace all comments with this text
+        #[arg(long, value_name = "TEXT")]
+        comment: Option<String>,
+
+        /// Add a comment after the existing ones (repeatable)
+        #[arg(long, value_name = "TEXT")]
+        append_comment: Vec<String>,
+
+        /// Remove every comment
+        #[arg(long, conflicts_with = "comment")]
+        clear_comments: bool,
+
+        /// Replace (or add) the XMP packet with the contents of this XML file
+        #[arg(long, value_name = "FILE")]
+        xmp: Option<PathBuf>,
+
+        /// Remove the XMP packet
+        #[arg(long, conflicts_with = "xmp")]
+        strip_xmp: bool,
+
+        /// Replace (or add) the ICC color profile with this .icc file
+        #[arg(long, value_name = "FILE")]
+        icc: Option<PathBuf>,
+
+        /// Remove the ICC color profile
+        #[arg(long, conflicts_with = "icc")]
+        strip_icc: bool,
+
+        /// Output Path to the GIF file ("-" writes stdout; the default when reading stdin)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        in_place: InPlaceArgs,
+
+        /// Check the result before writing it: every frame must render identically,
+        /// requested values must be in place and nothing else may change
+        #[arg(long)]
+        verify: bool,
+
+        /// Print what would change (delays, loop count, blocks, size) without writing anything
+        #[arg(long, conflicts_with_all = ["output", "in_place"])]
+        dry_run: bool,
+
+        /// With --dry-run, print the plan as json
+        #[arg(long, requires = "dry_run")]
+        json: bool,
+    },
+
+    /// List every block with its offset, length and decoded fields
+    Blocks {
+        /// Path to the GIF file ("-" reads stdin)
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Get output as json
         #[arg(long)]
-        delay: Option<u16>,
+        json: bool,
 
-        /// Delays example 1=10,5=20
+        /// Include the raw bytes of every block
         #[arg(long)]
-        delays: Option<String>,
+        hex: bool,
+    },
+
+    /// Compare the metadata of two GIFs; exits with status 1 if they differ
+    Diff {
+        /// Path to the first GIF file ("-" reads stdin)
+        #[arg(value_name = "LEFT")]
+        left: PathBuf,
+
+        /// Path to the second GIF file ("-" reads stdin)
+        #[arg(value_name = "RIGHT")]
+        right: PathBuf,
 
-        /// Output Path to the GIF file
+        /// Also render every frame of both files and compare the pixels
+        #[arg(long)]
+        pixels: bool,
+
+        /// Get output as json
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check a GIF against the spec; exits with status 1 if any error is found
+    Lint {
+        /// Path to the GIF file ("-" reads stdin)
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Get output as json
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Salvage a truncated or malformed GIF into a valid file
+    Repair {
+        /// Path to the GIF file ("-" reads stdin)
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Output Path to the GIF file ("-" writes stdout; the default when reading stdin)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        in_place: InPlaceArgs,
+    },
+
+    /// Remove all metadata that is not needed to render the GIF
+    Strip {
+        /// Path to the GIF file ("-" reads stdin)
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Comma-separated categories to keep: loop, comments, xmp, icc,
+        /// applications, plain-text, extensions, trailing (use "" to keep nothing)
+        #[arg(long, value_name = "LIST", default_value = "loop")]
+        keep: String,
+
+        /// Output Path to the GIF file ("-" writes stdout; the default when reading stdin)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        #[command(flatten)]
+        in_place: InPlaceArgs,
+    },
+
+    /// Export the embedded ICC color profile to a .icc file
+    Icc {
+        /// Path to the GIF file ("-" reads stdin)
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Path to save the profile (e.g. profile.icc, or "-" for stdout)
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Extract the XMP metadata packet as XML
+    Xmp {
+        /// Path to the GIF file ("-" reads stdin)
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Path to save the XML (prints to stdout if omitted)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
     },
 
     /// Preview a single frame of a GIF file as PNG or base64.
     Preview {
-        /// Path to the GIF file
+        /// Path to the GIF file ("-" reads stdin)
         #[arg(value_name = "INPUT")]
         input: PathBuf,
 
@@ -64,7 +577,11 @@ enum Commands {
         #[arg(long)]
         as_base64: bool,
 
-        /// Path to save the extracted PNG frame (e.g. frame0.png)
+        /// Draw Plain Text Extensions using a built-in bitmap font
+        #[arg(long)]
+        render_text: bool,
+
+        /// Path to save the extracted PNG frame (e.g. frame0.png, or "-" for stdout)
         #[arg(short, long, value_name = "FILE")]
         output: Option<PathBuf>,
     },
@@ -78,23 +595,78 @@ fn main() {
             path,
             show_frames,
             json,
+            tolerant,
         } => {
-            let meta_result = gifmeta::get_metadata(&path, show_frames);
-            let meta_data = meta_result.ok();
+            let meta_result = read_input(&path).and_then(|data| {
+                if tolerant {
+                    gifmeta::get_metadata_tolerant_from_bytes(&data, show_frames)
+                } else {
+                    gifmeta::get_metadata_from_bytes(&data, show_frames)
+                }
+            });
+            if let Err(e) = &meta_result {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
 
-            if let Some(meta) = meta_data {
+            if let Ok(meta) = meta_result {
                 if json {
                     let json_str = serde_json::to_string_pretty(&meta).unwrap();
                     println!("{}", json_str);
                 } else {
-                    println!("✅ Metadata for : {}\n", path.display());
+                    for warning in &meta.warnings {
+                        eprintln!("⚠️  {}", warning);
+                    }
+                    println!("✅ Metadata for : {}\n", input_name(&path));
                     println!("🖼️ Dimensions   : {} × {}", meta.width, meta.height);
                     println!("🖼️ Frame count  : {}", meta.frame_count);
                     println!("⏱️ Duration     : {} centiseconds", meta.total_duration_cs);
-                    println!("🔄Loop         : {:?}", meta.loop_count);
+                    println!("🔄Loop         : {}", plays_text(meta.total_plays));
+                    println!(
+                        "🔄Repeat field : {}",
+                        match (meta.has_loop_extension, meta.repeat_count) {
+                            (false, _) => "none (no loop extension)".to_string(),
+                            (true, Some(raw)) => raw.to_string(),
+                            (true, None) => "invalid".to_string(),
+                        }
+                    );
+                    if let Some(id) = meta
+                        .loop_identifier
+                        .as_deref()
+                        .filter(|id| *id != "NETSCAPE2.0")
+                    {
+                        println!("🔄Loop block   : {}", id);
+                    }
+                    if let Some(size) = meta.loop_buffer_size {
+                        println!("🔄Loop buffer  : {} bytes", size);
+                    }
                     println!("🎨Has palette  : {:?}", meta.has_global_palette);
                     println!("🎨Palette size : {:?}", meta.global_palette_size.unwrap());
                     println!("🎨Transparency : {:?}", meta.uses_transparency);
+                    if let Some(icc) = &meta.icc_profile {
+                        println!(
+                            "🎨ICC profile  : {} bytes{}",
+                            icc.size,
+                            icc.description
+                                .as_ref()
+                                .map(|d| format!(" ({})", d))
+                                .unwrap_or_default()
+                        );
+                    }
+                    if let Some(size) = meta.xmp_size {
+                        println!("📝XMP          : {} bytes", size);
+                    }
+                    for comment in &meta.comments {
+                        println!("💬Comment      : {}", comment.text);
+                    }
+                    if !show_frames {
+                        for text in &meta.plain_text {
+                            println!(
+                                "🔤Plain text   : frame {} at {},{}: {:?}",
+                                text.frame, text.left, text.top, text.text
+                            );
+                        }
+                    }
                     if show_frames {
                         println!("\n🧩Frame delays :");
                         for frame in &meta.frames {
@@ -107,6 +679,18 @@ fn main() {
                                     None => "".to_string(),
                                 }
                             );
+                            for text in &frame.plain_text {
+                                println!(
+                                    "      🔤 Plain text at {},{} ({}×{} cells of {}×{}): {:?}",
+                                    text.left,
+                                    text.top,
+                                    text.width / text.cell_width.max(1) as u16,
+                                    text.height / text.cell_height.max(1) as u16,
+                                    text.cell_width,
+                                    text.cell_height,
+                                    text.text
+                                );
+                            }
                         }
                     }
                 }
@@ -115,38 +699,381 @@ fn main() {
         Commands::Mod {
             input,
             loop_count,
-            delay,
-            delays,
+            no_loop,
+            loop_buffer,
+            normalize_loop,
+            timing,
+            comment,
+            append_comment,
+            clear_comments,
+            xmp,
+            strip_xmp,
+            icc,
+            strip_icc,
             output,
+            in_place,
+            verify,
+            dry_run,
+            json,
         } => {
-            let delays_map = delays
-                .as_ref()
-                .map(|s| utils::parse_csv::parse_keyval_csv(s))
-                .transpose()
-                .unwrap_or(None);
-            let _ = gifmeta::mod_gif(&input, output, loop_count, delay, delays_map);
+            let icc_profile = match icc.map(std::fs::read).transpose() {
+                Ok(profile) => profile,
+                Err(e) => {
+                    eprintln!("❌ Failed to read ICC profile: {}", e);
+                    std::process::exit(2);
+                }
+            };
+            let xmp = match xmp.map(std::fs::read_to_string).transpose() {
+                Ok(xml) => xml,
+                Err(e) => {
+                    eprintln!("❌ Failed to read XMP file: {}", e);
+                    std::process::exit(2);
+                }
+            };
+            let mut options = ModifyOptions {
+                loop_count,
+                remove_loop: no_loop,
+                loop_buffer_size: loop_buffer,
+                normalize_loop,
+                comment,
+                append_comments: append_comment,
+                clear_comments,
+                xmp,
+                strip_xmp,
+                icc_profile,
+                strip_icc,
+                verify,
+                ..Default::default()
+            };
+            if let Err(e) = timing.apply(&mut options) {
+                eprintln!("❌ {}", e);
+                std::process::exit(2);
+            }
+            if dry_run {
+                match read_input(&input)
+                    .and_then(|data| gifmeta::plan_mod_gif_from_bytes(&data, &options))
+                {
+                    Ok(plan) if json => {
+                        println!("{}", serde_json::to_string_pretty(&plan).unwrap())
+                    }
+                    Ok(plan) => print_plan(&input, &plan),
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        let usage = matches!(e, GifError::NoModifications);
+                        std::process::exit(if usage { 2 } else { 1 });
+                    }
+                }
+                return;
+            }
+            let to_stderr = writes_stdout(&input, &output);
+            let result = run_edit(
+                &input,
+                &output,
+                &in_place,
+                "mod",
+                "Modifications applied",
+                |data| gifmeta::mod_gif_from_bytes(data, &options),
+            );
+            match result {
+                Ok(report) => {
+                    if !report.preserved.is_empty() {
+                        status!(
+                            to_stderr,
+                            "📦 Preserved {} extension block(s)",
+                            report.preserved.len()
+                        );
+                    }
+                    if let Some(timing) = &report.timing {
+                        status!(to_stderr, "⏱️ Duration: {}", timing_text(timing));
+                    }
+                    if let Some(verification) = &report.verification {
+                        status!(
+                            to_stderr,
+                            "🔒 Verified: {} frame(s) render identically and only the requested values changed",
+                            verification.frames_compared
+                        );
+                    }
+                }
+                Err(GifError::VerificationFailed { problems }) => {
+                    eprintln!("❌ Verification failed, nothing was written:");
+                    for problem in &problems {
+                        eprintln!("  • {}", problem);
+                    }
+                    std::process::exit(1);
+                }
+                Err(GifError::NoModifications) => {
+                    eprintln!("⚠️  No modifications specified.");
+                    eprintln!("   Use at least one of: --loop-count, --no-loop, --delay, --delays, --speed, --slow, --fps, --duration, or a comment, XMP or ICC option");
+                    std::process::exit(2);
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Blocks { input, json, hex } => match read_input(&input)
+            .and_then(|data| gifmeta::dump_blocks_from_bytes(&data, hex))
+        {
+            Ok(blocks) if json => {
+                println!("{}", serde_json::to_string_pretty(&blocks).unwrap());
+            }
+            Ok(blocks) => {
+                println!("{:<10} {:>8}  {:<26} Fields", "Offset", "Length", "Block");
+                for block in &blocks {
+                    let fields: Vec<String> = block
+                        .fields
+                        .iter()
+                        .filter(|(_, value)| !value.is_null())
+                        .map(|(name, value)| format!("{}={}", name, value))
+                        .collect();
+                    let line = format!(
+                        "0x{:08X} {:>8}  {:<26} {}",
+                        block.offset,
+                        block.len,
+                        block.name,
+                        fields.join(" ")
+                    );
+                    println!("{}", line.trim_end());
+                    if let Some(hex) = &block.hex {
+                        for (row, line) in hex.as_bytes().chunks(32).enumerate() {
+                            let bytes: Vec<&str> = line
+                                .chunks(2)
+                                .map(|pair| std::str::from_utf8(pair).unwrap())
+                                .collect();
+                            println!("    {:08x}: {}", block.offset + row * 16, bytes.join(" "));
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Diff {
+            left,
+            right,
+            pixels,
+            json,
+        } => {
+            let options = DiffOptions {
+                compare_pixels: pixels,
+            };
+            let result = read_input(&left).and_then(|l| {
+                let r = read_input(&right)?;
+                gifmeta::diff_gifs_from_bytes(&l, &r, &options)
+            });
+            match result {
+                Ok(diff) => {
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&diff).unwrap());
+                    } else {
+                        print_diff(&left, &right, &diff);
+                    }
+                    if !diff.is_identical() {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
+        Commands::Lint { input, json } => {
+            match read_input(&input).map(|data| gifmeta::lint_gif_from_bytes(&data)) {
+                Ok(report) => {
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+                    } else {
+                        for finding in &report.findings {
+                            println!(
+                                "{}[{}] at 0x{:08X}: {}",
+                                finding.severity, finding.code, finding.offset, finding.message
+                            );
+                        }
+                        if report.findings.is_empty() {
+                            println!("✅ {} conforms to the spec", input_name(&input));
+                        } else {
+                            println!(
+                                "\n{} error(s), {} warning(s)",
+                                report.errors, report.warnings
+                            );
+                        }
+                    }
+                    if report.has_errors() {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
+        Commands::Repair {
+            input,
+            output,
+            in_place,
+        } => {
+            let to_stderr = writes_stdout(&input, &output);
+            let result = run_edit(
+                &input,
+                &output,
+                &in_place,
+                "repair",
+                "Repaired",
+                gifmeta::repair_gif_from_bytes,
+            );
+            match result {
+                Ok(report) => {
+                    for fix in &report.fixes {
+                        status!(to_stderr, "  • 0x{:08X}: {}", fix.offset, fix.message);
+                    }
+                    if report.fixes.is_empty() {
+                        status!(to_stderr, "Nothing to repair");
+                    }
+                    status!(
+                        to_stderr,
+                        "🩹 {} frame(s), {} → {} bytes",
+                        report.frames,
+                        report.original_size,
+                        report.repaired_size
+                    );
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Strip {
+            input,
+            keep,
+            output,
+            in_place,
+        } => {
+            let keep = match keep
+                .split(',')
+                .filter(|part| !part.trim().is_empty())
+                .map(str::parse::<StripCategory>)
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(keep) => keep,
+                Err(e) => {
+                    eprintln!("❌ Invalid --keep: {}", e);
+                    std::process::exit(2);
+                }
+            };
+            let options = StripOptions { keep };
+            let to_stderr = writes_stdout(&input, &output);
+            let result = run_edit(
+                &input,
+                &output,
+                &in_place,
+                "strip",
+                "Metadata stripped",
+                |data| gifmeta::strip_gif_from_bytes(data, &options),
+            );
+            match result {
+                Ok(report) => {
+                    for entry in &report.removed {
+                        status!(
+                            to_stderr,
+                            "  • {:<12}: {} block(s), {} bytes",
+                            entry.category.as_str(),
+                            entry.blocks,
+                            entry.bytes
+                        );
+                    }
+                    status!(
+                        to_stderr,
+                        "🧹 Removed {} bytes ({} → {})",
+                        report.original_size - report.stripped_size,
+                        report.original_size,
+                        report.stripped_size
+                    );
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Icc { input, output } => {
+            match read_input(&input).and_then(|data| gifmeta::get_icc_profile_from_bytes(&data)) {
+                Ok(Some(profile)) => {
+                    write_output_or_exit(&output, &profile);
+                    status!(
+                        is_stdio(&output),
+                        "ICC profile ({} bytes) written to {}",
+                        profile.len(),
+                        output_name(&output)
+                    );
+                }
+                Ok(None) => {
+                    eprintln!("No ICC profile in {}", input_name(&input));
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Xmp { input, output } => {
+            match read_input(&input).and_then(|data| gifmeta::get_xmp_from_bytes(&data)) {
+                Ok(Some(xml)) => match output.filter(|path| !is_stdio(path)) {
+                    Some(out_path) => {
+                        write_output_or_exit(&out_path, xml.as_bytes());
+                        println!("XMP written to {}", out_path.display());
+                    }
+                    None => print!("{}", xml),
+                },
+                Ok(None) => {
+                    eprintln!("No XMP packet in {}", input_name(&input));
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            }
         }
         Commands::Preview {
             input,
             frame,
             as_base64,
+            render_text,
             output,
         } => {
             let frame_index = frame.unwrap_or(0) as usize;
-            match gifmeta::get_frame_image(input.to_string_lossy().to_string(), frame_index) {
+            let options = PreviewOptions {
+                render_plain_text: render_text,
+            };
+            match read_input(&input)
+                .and_then(|data| gifmeta::get_frame_image_from_bytes(&data, frame_index, &options))
+            {
                 Ok(png_bytes) => {
                     if as_base64 {
                         let encoded = base64::engine::general_purpose::STANDARD.encode(&png_bytes);
                         println!("data:image/png;base64,{}", encoded);
                     } else if let Some(out_path) = output {
-                        std::fs::write(&out_path, png_bytes).expect("Failed to write output PNG");
-                        println!("Frame {} written to {}", frame_index, out_path.display());
+                        write_output_or_exit(&out_path, &png_bytes);
+                        status!(
+                            is_stdio(&out_path),
+                            "Frame {} written to {}",
+                            frame_index,
+                            output_name(&out_path)
+                        );
                     } else {
                         eprintln!("Specify either --as-base64 or --output <path>");
                     }
                 }
                 Err(e) => {
                     eprintln!("Error extracting frame: {}", e);
+                    std::process::exit(1);
                 }
             }
         }
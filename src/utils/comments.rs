@@ -0,0 +1,77 @@
+use crate::blocks::{Block, GifFile, EXTENSION_INTRODUCER, LABEL_COMMENT};
+use crate::gifmeta_structs::CommentMeta;
+use crate::utils::patch::{self, Patch};
+
+/// Lists every Comment Extension in file order.
+///
+/// Comment text is decoded as UTF-8, replacing invalid sequences; the raw
+/// bytes are kept alongside for anything that is not plain text.
+pub fn read_comments(gif: &GifFile) -> Vec<CommentMeta> {
+    gif.blocks
+        .iter()
+        .filter_map(|block| match block {
+            Block::Comment(comment) => {
+                let bytes = comment.data.collect(&gif.data);
+                Some(CommentMeta {
+                    offset: comment.span.offset,
+                    text: String::from_utf8_lossy(&bytes).into_owned(),
+                    bytes,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Builds a Comment Extension holding `text`.
+pub fn comment_block(text: &[u8]) -> Vec<u8> {
+    let mut block = vec![EXTENSION_INTRODUCER, LABEL_COMMENT];
+    block.extend(patch::sub_blocks(text));
+    block
+}
+
+/// Adds comment edits to `patch`.
+///
+/// - `clear`: remove every existing comment
+/// - `replace`: remove every existing comment and write this one in place of the first
+/// - `append`: add these comments after the last existing comment
+///
+/// When existing comments are removed, new ones take the place of the first;
+/// otherwise appended comments follow the last one. Without any existing
+/// comment, new ones go right before the first frame.
+pub fn comment_edits(
+    gif: &GifFile,
+    patch: &mut Patch,
+    clear: bool,
+    replace: Option<&str>,
+    append: &[String],
+) {
+    let existing: Vec<_> = gif
+        .blocks
+        .iter()
+        .filter(|b| matches!(b, Block::Comment(_)))
+        .map(|b| b.span())
+        .collect();
+
+    let default_position = patch::before_first_frame(gif);
+
+    // Replacement and appended comments go where the first removed one was
+    let position = if clear || replace.is_some() {
+        existing.first().map_or(default_position, |s| s.offset)
+    } else {
+        existing.last().map_or(default_position, |s| s.end())
+    };
+
+    if let Some(text) = replace {
+        patch.insert(position, comment_block(text.as_bytes()));
+    }
+    for text in append {
+        patch.insert(position, comment_block(text.as_bytes()));
+    }
+
+    if clear || replace.is_some() {
+        for span in existing {
+            patch.remove(span);
+        }
+    }
+}
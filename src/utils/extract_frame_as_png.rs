@@ -1,27 +1,92 @@
 use gif::DecodeOptions;
 use image::{Rgba, RgbaImage};
-use std::fs::File;
-use std::io::{BufReader, Cursor};
+use std::fs;
+use std::io::Cursor;
+use std::ops::ControlFlow;
+
+use crate::blocks;
+use crate::gifmeta_structs::{GifError, PreviewOptions};
+use crate::utils::plain_text;
+
+pub fn extract_frame_as_png(path: &str, frame_index: usize) -> Result<Vec<u8>, GifError> {
+    extract_frame_as_png_with_options(path, frame_index, &PreviewOptions::default())
+}
+
+/// Like [`extract_frame_as_png`], optionally drawing Plain Text Extensions
+/// over the frames they follow.
+pub fn extract_frame_as_png_with_options(
+    path: &str,
+    frame_index: usize,
+    options: &PreviewOptions,
+) -> Result<Vec<u8>, GifError> {
+    let data = fs::read(path).map_err(|e| GifError::io(path, e))?;
+    frame_png_from_bytes(&data, frame_index, options)
+}
+
+/// Extracts a frame from the raw bytes of a GIF as PNG-encoded bytes.
+pub fn frame_png_from_bytes(
+    data: &[u8],
+    frame_index: usize,
+    options: &PreviewOptions,
+) -> Result<Vec<u8>, GifError> {
+    let mut png = None;
+    let frame_count = render_frames(data, options, |index, canvas| {
+        if index < frame_index {
+            return Ok(ControlFlow::Continue(()));
+        }
+        // Return snapshot of canvas at this point
+        let mut buf = Vec::new();
+        canvas.write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Png)?;
+        png = Some(buf);
+        Ok(ControlFlow::Break(()))
+    })?;
+
+    png.ok_or(GifError::FrameOutOfRange {
+        index: frame_index,
+        frame_count,
+    })
+}
+
+/// Composites the frames of a GIF onto the logical screen one by one and
+/// passes the canvas after each frame to `visit`, until it breaks.
+///
+/// Returns the number of frames rendered.
+pub fn render_frames(
+    data: &[u8],
+    options: &PreviewOptions,
+    mut visit: impl FnMut(usize, &RgbaImage) -> Result<ControlFlow<()>, GifError>,
+) -> Result<usize, GifError> {
+    // Plain text is not decoded by `gif`, so it is placed using the block view
+    let parsed = if options.render_plain_text {
+        Some(blocks::parse(data.to_vec())?)
+    } else {
+        None
+    };
+    let texts = parsed
+        .as_ref()
+        .map(plain_text::plain_text_blocks)
+        .unwrap_or_default();
+    let draw_texts = |canvas: &mut RgbaImage, images_before: usize| {
+        if let Some(parsed) = &parsed {
+            for text in texts.iter().filter(|t| t.images_before == images_before) {
+                plain_text::render_plain_text(canvas, parsed, text);
+            }
+        }
+    };
 
-pub fn extract_frame_as_png(path: &str, frame_index: usize) -> Result<Vec<u8>, String> {
-    let file = File::open(path).map_err(|e| format!("File open failed: {e}"))?;
     let mut decoder = DecodeOptions::new();
     decoder.set_color_output(gif::ColorOutput::RGBA);
-    let mut reader = decoder
-        .read_info(BufReader::new(file))
-        .map_err(|e| format!("GIF decode failed: {e}"))?;
+    let mut reader = decoder.read_info(Cursor::new(data))?;
 
     // Get canvas size from logical screen
     let canvas_width = reader.width() as u32;
     let canvas_height = reader.height() as u32;
 
     let mut canvas = RgbaImage::from_pixel(canvas_width, canvas_height, Rgba([0, 0, 0, 0]));
+    draw_texts(&mut canvas, 0);
 
     let mut frame_count = 0;
-    while let Some(frame) = reader
-        .read_next_frame()
-        .map_err(|e| format!("Read frame error: {e}"))?
-    {
+    while let Some(frame) = reader.read_next_frame()? {
         // Composite frame onto canvas
         //let buffer = &frame.buffer;
         for y in 0..frame.height {
@@ -41,17 +106,15 @@ pub fn extract_frame_as_png(path: &str, frame_index: usize) -> Result<Vec<u8>, S
             }
         }
 
-        if frame_count == frame_index {
-            // Return snapshot of canvas at this point
-            let mut buf = Vec::new();
-            canvas
-                .write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Png)
-                .map_err(|e| format!("PNG encode error: {e}"))?;
-            return Ok(buf);
-        }
+        // Text between this image and the next is shown over this frame
+        draw_texts(&mut canvas, frame_count + 1);
 
+        let flow = visit(frame_count, &canvas)?;
         frame_count += 1;
+        if flow.is_break() {
+            break;
+        }
     }
 
-    Err(format!("Frame index {} out of bounds", frame_index))
+    Ok(frame_count)
 }
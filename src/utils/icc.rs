@@ -0,0 +1,118 @@
+use crate::blocks::{Application, GifFile, EXTENSION_INTRODUCER, LABEL_APPLICATION};
+use crate::gifmeta_structs::{GifError, IccMeta};
+use crate::utils::patch::{self, Patch};
+
+/// Application identifier for embedded ICC profiles.
+pub const ICC_IDENTIFIER: &str = "ICCRGBG1012";
+
+/// Iterates over ICC profile application extensions in file order.
+pub fn icc_blocks(gif: &GifFile) -> impl Iterator<Item = &Application> {
+    gif.applications()
+        .filter(|app| app.identifier == ICC_IDENTIFIER)
+}
+
+/// Reassembles the ICC profile from the sub-blocks of the first ICC extension.
+pub fn read_icc(gif: &GifFile) -> Option<Vec<u8>> {
+    icc_blocks(gif)
+        .next()
+        .map(|app| app.data.collect(&gif.data))
+}
+
+/// Summarizes the embedded ICC profile, if any.
+pub fn icc_meta(gif: &GifFile) -> Option<IccMeta> {
+    read_icc(gif).map(|profile| IccMeta {
+        size: profile.len(),
+        description: icc_description(&profile),
+    })
+}
+
+/// Checks the fixed parts of an ICC profile header.
+pub fn validate_profile(profile: &[u8]) -> Result<(), GifError> {
+    if profile.len() < 132 || &profile[36..40] != b"acsp" {
+        return Err(GifError::invalid_spec(
+            "ICC profile",
+            "Not an ICC profile (missing 'acsp' signature)",
+        ));
+    }
+    let declared = be_u32(profile, 0).unwrap_or(0) as usize;
+    if declared != profile.len() {
+        return Err(GifError::invalid_spec(
+            "ICC profile",
+            format!(
+                "Header declares {} bytes but the profile is {} bytes",
+                declared,
+                profile.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the profile description (`desc` tag).
+///
+/// Supports the ICC v2 `textDescriptionType` and the v4
+/// `multiLocalizedUnicodeType` (first record).
+pub fn icc_description(profile: &[u8]) -> Option<String> {
+    // The count is untrusted: never look past the entries the profile holds
+    let entries = profile.len().saturating_sub(132) / 12;
+    let tag_count = (be_u32(profile, 128)? as usize).min(entries);
+    let (offset, size) = (0..tag_count).find_map(|i| {
+        let entry = 132 + i * 12;
+        (profile.get(entry..entry + 4)? == b"desc")
+            .then(|| Some((be_u32(profile, entry + 4)?, be_u32(profile, entry + 8)?)))?
+    })?;
+    let tag = profile.get(offset as usize..(offset as usize).checked_add(size as usize)?)?;
+
+    match tag.get(0..4)? {
+        b"desc" => {
+            let len = be_u32(tag, 8)? as usize;
+            let text = tag.get(12..12 + len)?;
+            let text = text.split(|&b| b == 0).next().unwrap_or(text);
+            Some(String::from_utf8_lossy(text).into_owned())
+        }
+        b"mluc" => {
+            let record_len = be_u32(tag, 20)? as usize;
+            let record_offset = be_u32(tag, 24)? as usize;
+            let units: Vec<u16> = tag
+                .get(record_offset..record_offset + record_len)?
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            Some(String::from_utf16_lossy(&units))
+        }
+        _ => None,
+    }
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Builds an ICC application extension, splitting the profile into sub-blocks.
+pub fn icc_block(profile: &[u8]) -> Vec<u8> {
+    let mut block = vec![EXTENSION_INTRODUCER, LABEL_APPLICATION, 0x0B];
+    block.extend_from_slice(ICC_IDENTIFIER.as_bytes());
+    block.extend(patch::sub_blocks(profile));
+    block
+}
+
+/// Adds ICC profile edits to `patch`.
+///
+/// `replace` rewrites the first ICC block (or adds one before the first frame)
+/// and drops any others; `strip` removes every ICC block.
+pub fn icc_edits(gif: &GifFile, patch: &mut Patch, replace: Option<&[u8]>, strip: bool) {
+    let existing: Vec<_> = icc_blocks(gif).map(|app| app.span).collect();
+
+    if let Some(profile) = replace {
+        let position = existing
+            .first()
+            .map_or_else(|| patch::before_first_frame(gif), |s| s.offset);
+        patch.insert(position, icc_block(profile));
+    }
+    if replace.is_some() || strip {
+        for span in existing {
+            patch.remove(span);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tempfile::NamedTempFile;
+use uuid::Uuid;
+
+use crate::gifmeta_structs::{GifError, InPlaceOptions};
+
+/// Path of the backup kept for `path` with the given suffix.
+pub fn backup_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Replaces the contents of `path` with `data` atomically.
+///
+/// The data is written to a temp file in the same directory, synced to disk
+/// and renamed over the original, so readers see either the old or the new
+/// file and never a partial one. The new file keeps the original's
+/// permissions. Symlinks are followed and their target is replaced.
+pub fn write_in_place(path: &Path, data: &[u8], options: &InPlaceOptions) -> Result<(), GifError> {
+    let target = fs::canonicalize(path).map_err(|e| GifError::io(path, e))?;
+    let io_error = |e| GifError::io(&target, e);
+    let original = fs::metadata(&target).map_err(io_error)?;
+    let dir = target.parent().unwrap_or(Path::new("."));
+
+    let mut temp = sibling_temp(dir).map_err(io_error)?;
+    temp.write_all(data).map_err(io_error)?;
+    let file = temp.as_file();
+    file.set_permissions(original.permissions())
+        .map_err(io_error)?;
+    if options.preserve_mtime {
+        file.set_modified(original.modified().map_err(io_error)?)
+            .map_err(io_error)?;
+    }
+    file.sync_all().map_err(io_error)?;
+
+    if let Some(suffix) = &options.backup_suffix {
+        keep_backup(&target, &backup_path(path, suffix))?;
+    }
+    temp.persist(&target).map_err(|e| io_error(e.error))?;
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Writes `data` to `path` atomically, creating the file if it does not exist.
+///
+/// An existing file is replaced like [`write_in_place`] does. A new file goes
+/// through the same synced temp file and rename, so a failed write never
+/// leaves a partial file at `path`.
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), GifError> {
+    if path.exists() {
+        return write_in_place(path, data, &InPlaceOptions::default());
+    }
+    let io_error = |e| GifError::io(path, e);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut temp = sibling_temp(dir).map_err(io_error)?;
+    temp.write_all(data).map_err(io_error)?;
+    temp.as_file().sync_all().map_err(io_error)?;
+    temp.persist(path).map_err(|e| io_error(e.error))?;
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Creates a temp file in `dir`, so renaming it over a file there never
+/// crosses filesystems.
+fn sibling_temp(dir: &Path) -> std::io::Result<NamedTempFile> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".gifmeta-").suffix(".tmp");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // The permissions a plain `fs::write` would give, minus the umask
+        builder.permissions(fs::Permissions::from_mode(0o666));
+    }
+    builder.tempfile_in(dir)
+}
+
+/// Makes a rename in `dir` durable; not every platform can sync a directory.
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Keeps the current contents of `target` at `backup`, replacing any older backup.
+fn keep_backup(target: &Path, backup: &Path) -> Result<(), GifError> {
+    match fs::remove_file(backup) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(GifError::io(backup, e)),
+        _ => {}
+    }
+    // A hard link keeps the original inode, timestamps included; copy where
+    // links are not supported
+    if fs::hard_link(target, backup).is_err() {
+        fs::copy(target, backup).map_err(|e| GifError::io(backup, e))?;
+    }
+    Ok(())
+}
+
+/// Writes `data` to `output`, or replaces `input` in place when there is no output.
+pub fn write_or_replace(
+    input: &Path,
+    output: Option<&Path>,
+    data: &[u8],
+) -> Result<PathBuf, GifError> {
+    let out_path = output.unwrap_or(input);
+    write_atomic(out_path, data)?;
+    Ok(out_path.to_path_buf())
+}
+
+/// A new `<uuid>.<kind>.gif` path in the system temp directory.
+pub fn temp_output(kind: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}.{}.gif", Uuid::new_v4(), kind))
+}
+
+/// Writes `data` to `output`, or to a new [`temp_output`] file when there is
+/// no output, so the input is never touched.
+pub fn write_or_temp(output: Option<&Path>, kind: &str, data: &[u8]) -> Result<PathBuf, GifError> {
+    let out_path = output.map_or_else(|| temp_output(kind), Path::to_path_buf);
+    write_atomic(&out_path, data)?;
+    Ok(out_path)
+}
+
+/// Reads `path`, applies `edit` to its bytes and writes the result back with
+/// [`write_in_place`]. The file is left untouched if `edit` fails.
+pub fn edit_in_place<R>(
+    path: &Path,
+    options: &InPlaceOptions,
+    edit: impl FnOnce(&[u8]) -> Result<(Vec<u8>, R), GifError>,
+) -> Result<R, GifError> {
+    let data = fs::read(path).map_err(|e| GifError::io(path, e))?;
+    let (data, report) = edit(&data)?;
+    write_in_place(path, &data, options)?;
+    Ok(report)
+}
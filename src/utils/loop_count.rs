@@ -1,95 +1,113 @@
-use std::fs::File;
-use std::io::{BufReader, Read, Write};
+use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 
-pub fn extract_loop_count<P: AsRef<Path>>(path: P) -> Result<u16, String> {
-    let mut file = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
+use crate::blocks::{self, GifFile};
+use crate::gifmeta_structs::{GifError, LoopExtension};
+use crate::utils::in_place;
+use crate::utils::patch::{self, LoopEdit};
 
-    let mut i = 0;
-    while i < buffer.len() {
-        if buffer[i] == 0x21 && buffer[i + 1] == 0xFF {
-            let _block_size = buffer[i + 2] as usize;
-            if &buffer[i + 3..i + 3 + 11] == b"NETSCAPE2.0" {
-                let loop_data_index = i + 3 + 11 + 2;
-                let loop_count =
-                    u16::from_le_bytes([buffer[loop_data_index], buffer[loop_data_index + 1]]);
-                return Ok(loop_count);
-            }
-        }
-        i += 1;
-    }
+/// Application identifiers that carry a loop count.
+pub const LOOP_EXTENSIONS: [&str; 2] = ["NETSCAPE2.0", "ANIMEXTS1.0"];
 
-    Err("Loop count not found".into())
+/// A NETSCAPE2.0 or ANIMEXTS1.0 application extension and the sub-blocks it carries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopBlock {
+    /// `"NETSCAPE2.0"` or `"ANIMEXTS1.0"`.
+    pub identifier: String,
+    /// Offset of the extension block.
+    pub offset: usize,
+    /// Raw repeat count from a valid `0x03 0x01` sub-block (0 = infinite).
+    pub repeat: Option<u16>,
+    /// Buffer size from a valid `0x05 0x02` sub-block.
+    pub buffer_size: Option<u32>,
 }
 
-/// Sets the loop count metadata in a GIF file.
-/// If NETSCAPE2.0 loop extension exists, it will be modified.
-/// If not found, it will be inserted right after the GIF header.
-pub fn set_loop_count(path: &PathBuf, count: u16, output: Option<PathBuf>) -> Result<(), String> {
-    let mut file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
-    let mut data = Vec::new();
-    file.read_to_end(&mut data)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+/// Reads the raw repeat count from the loop extension.
+///
+/// Returns [`GifError::MissingLoopExtension`] if the file has none, and a
+/// [`GifError::Decode`] pointing at the block if it is malformed.
+pub fn extract_loop_count<P: AsRef<Path>>(path: P) -> Result<u16, GifError> {
+    match read_loop_extension(&blocks::read_file(path)?) {
+        LoopExtension::Count { repeat, .. } => Ok(repeat),
+        LoopExtension::Missing => Err(GifError::MissingLoopExtension),
+        LoopExtension::Malformed { offset } => Err(GifError::decode(
+            offset,
+            "Loop extension has no valid loop sub-block",
+        )),
+    }
+}
 
-    let netscape_sig: &[u8] = b"NETSCAPE2.0";
-    let mut i = 0;
-    let mut found = false;
+/// Lists every loop extension in file order, decoding its known sub-blocks.
+///
+/// Only real application extensions are considered, so a `NETSCAPE2.0` byte
+/// sequence inside image data or a comment is never mistaken for one.
+pub fn loop_blocks(gif: &GifFile) -> Vec<LoopBlock> {
+    gif.applications()
+        .filter(|app| LOOP_EXTENSIONS.contains(&app.identifier.as_str()))
+        .map(|app| {
+            let mut block = LoopBlock {
+                identifier: app.identifier.clone(),
+                offset: app.span.offset,
+                repeat: None,
+                buffer_size: None,
+            };
+            for chunk in &app.data.chunks {
+                let bytes = gif.bytes(*chunk);
+                match (bytes[0], bytes.len()) {
+                    (0x01, 3) if block.repeat.is_none() => {
+                        block.repeat = Some(u16::from_le_bytes([bytes[1], bytes[2]]));
+                    }
+                    (0x02, 5) if block.buffer_size.is_none() => {
+                        block.buffer_size =
+                            Some(u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]));
+                    }
+                    _ => {}
+                }
+            }
+            block
+        })
+        .collect()
+}
 
-    while i + 19 < data.len() {
-        // Look for the NETSCAPE2.0 signature inside an application extension block
-        if data[i] == 0x21
-            && data[i + 1] == 0xFF
-            && data[i + 2] == 0x0B
-            && &data[i + 3..i + 14] == netscape_sig
-            && data[i + 14] == 0x03
-            && data[i + 15] == 0x01
-        {
-            // Replace loop count
-            let count_le = count.to_le_bytes();
-            data[i + 16] = count_le[0];
-            data[i + 17] = count_le[1];
-            found = true;
-            break;
-        }
-        i += 1;
+/// Looks up the loop extension that decoders honour: the first NETSCAPE2.0
+/// or ANIMEXTS1.0 block in the file.
+pub fn read_loop_extension(gif: &GifFile) -> LoopExtension {
+    match loop_blocks(gif).first() {
+        None => LoopExtension::Missing,
+        Some(LoopBlock {
+            offset,
+            repeat: Some(repeat),
+            ..
+        }) => LoopExtension::Count {
+            offset: *offset,
+            repeat: *repeat,
+        },
+        Some(block) => LoopExtension::Malformed {
+            offset: block.offset,
+        },
     }
+}
 
-    if !found {
-        // Insert a new loop block after the header
-        // Find the end of the header: typically after the first image descriptor or GCT
-        // For simplicity, we insert after logical screen descriptor (at offset 13 + GCT length)
-        let gct_flag = data[10] & 0b1000_0000 != 0;
-        let gct_size = if gct_flag {
-            3 * (2_usize.pow(((data[10] & 0b0000_0111) + 1) as u32))
-        } else {
-            0
-        };
-        let insert_pos = 13 + gct_size;
+/// Sets the loop count metadata in a GIF file.
+/// If NETSCAPE2.0 loop extension exists, it will be modified.
+/// If not found, it will be inserted right after the global color table.
+pub fn set_loop_count(path: &PathBuf, count: u16, output: Option<PathBuf>) -> Result<(), GifError> {
+    let gif = blocks::read_file(path)?;
+    let data = patch::patch_metadata(&gif, LoopEdit::Set(count).into(), &HashMap::new());
 
-        let mut new_block = vec![
-            0x21, 0xFF, 0x0B, // Extension Introducer + Application Label
-        ];
-        new_block.extend_from_slice(netscape_sig); // "NETSCAPE2.0"
-        new_block.extend_from_slice(&[
-            0x03,
-            0x01,
-            count as u8,
-            (count >> 8) as u8,
-            0x00, // Block terminator
-        ]);
+    in_place::write_or_replace(path, output.as_deref(), &data)?;
 
-        data.splice(insert_pos..insert_pos, new_block);
-    }
+    Ok(())
+}
+
+/// Removes every NETSCAPE2.0 / ANIMEXTS1.0 loop extension from a GIF file.
+/// Without one, the GIF plays exactly once.
+pub fn remove_loop_count(path: &PathBuf, output: Option<PathBuf>) -> Result<(), GifError> {
+    let gif = blocks::read_file(path)?;
+    let data = patch::patch_metadata(&gif, LoopEdit::Remove.into(), &HashMap::new());
 
-    let out_path = output.unwrap_or_else(|| path.clone());
-    let mut out_file =
-        File::create(out_path).map_err(|e| format!("Failed to write file: {}", e))?;
-    out_file
-        .write_all(&data)
-        .map_err(|e| format!("Failed to save GIF: {}", e))?;
+    in_place::write_or_replace(path, output.as_deref(), &data)?;
 
     Ok(())
 }
@@ -1,4 +1,11 @@
+pub mod bitmap_font;
+pub mod comments;
 pub mod extract_frame_as_png;
+pub mod icc;
+pub mod in_place;
 pub mod loop_count;
 pub mod parse_csv;
+pub mod patch;
+pub mod plain_text;
 pub mod set_frame_delay;
+pub mod xmp;
@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::gifmeta_structs::{DelayOp, FrameDelay, FrameIndex, FrameSelector, GifError};
 
 /// Parses a comma-separated string into a vector of values.
 /// Used for parsing frame numbers or delay values from CLI.
@@ -9,34 +12,292 @@ use std::collections::HashMap;
 /// let values = parse_csv::<u16>("10,20,30").unwrap();
 /// assert_eq!(values, vec![10, 20, 30]);
 /// ```
-pub fn parse_csv<T: std::str::FromStr>(input: &str) -> Result<Vec<T>, String> {
+pub fn parse_csv<T: std::str::FromStr>(input: &str) -> Result<Vec<T>, GifError> {
     input
         .split(',')
         .map(|s| {
             s.trim()
                 .parse::<T>()
-                .map_err(|_| format!("Invalid value in list: '{}'", s))
+                .map_err(|_| GifError::invalid_spec(s, "Invalid value in list"))
         })
         .collect()
 }
 
 /// Parses a key=value CSV string like `"1=50,2=100"` into a HashMap<usize, u16>.
 /// Returns an error if any part of the string fails to parse.
-pub fn parse_keyval_csv(input: &str) -> Result<HashMap<usize, u16>, String> {
+pub fn parse_keyval_csv(input: &str) -> Result<HashMap<usize, u16>, GifError> {
     let mut map = HashMap::new();
     for part in input.split(',') {
         let (k, v) = part
             .split_once('=')
-            .ok_or_else(|| format!("Invalid format: '{}'", part))?;
+            .ok_or_else(|| GifError::invalid_spec(part, "Invalid format"))?;
         let key = k
             .trim()
             .parse::<usize>()
-            .map_err(|_| format!("Invalid frame index: '{}'", k))?;
+            .map_err(|_| GifError::invalid_spec(k, "Invalid frame index"))?;
         let val = v
             .trim()
             .parse::<u16>()
-            .map_err(|_| format!("Invalid delay value: '{}'", v))?;
+            .map_err(|_| GifError::invalid_spec(v, "Invalid delay value"))?;
         map.insert(key, val);
     }
     Ok(map)
 }
+
+/// Error for `token`, found `offset` bytes into the whole value.
+fn token_error(token: &str, offset: usize, message: &str) -> GifError {
+    let leading = token.len() - token.trim_start().len();
+    GifError::invalid_spec(
+        token.trim(),
+        format!("{} at character {}", message, offset + leading + 1),
+    )
+}
+
+/// Reads a frame index at the start of `text`: `N`, `-N`, `first` or `last`.
+/// Returns the index and the number of bytes it took.
+fn parse_frame_index(text: &str, offset: usize) -> Result<(FrameIndex, usize), GifError> {
+    for (keyword, index) in [
+        ("first", FrameIndex::FromStart(0)),
+        ("last", FrameIndex::FromEnd(1)),
+    ] {
+        if text.starts_with(keyword) {
+            return Ok((index, keyword.len()));
+        }
+    }
+    let negative = text.starts_with('-');
+    let digits_start = usize::from(negative);
+    let digits = text[digits_start..]
+        .bytes()
+        .take_while(u8::is_ascii_digit)
+        .count();
+    let number = &text[..digits_start + digits];
+    let value = text[digits_start..digits_start + digits]
+        .parse::<usize>()
+        .map_err(|_| {
+            token_error(
+                text,
+                offset,
+                "Invalid frame index (expected N, -N, first or last)",
+            )
+        })?;
+    match (negative, value) {
+        (true, 0) => Err(token_error(
+            number,
+            offset,
+            "Negative indexes start at -1 (the last frame)",
+        )),
+        (true, back) => Ok((FrameIndex::FromEnd(back), number.len())),
+        (false, index) => Ok((FrameIndex::FromStart(index), number.len())),
+    }
+}
+
+/// Parses a frame selector: `3`, `-1`, `first`, `last`, a range `0-9`, an
+/// open range `10-` (up to the last frame) or `-3--1`, and any range followed
+/// by a step such as `0-20/2`.
+///
+/// # Example
+/// ```
+/// use gifmeta::utils::parse_csv::parse_frame_selector;
+/// let selector = parse_frame_selector("0-20/2").unwrap();
+/// assert_eq!(selector.frames(8), vec![0, 2, 4, 6]);
+/// assert_eq!(parse_frame_selector("-1").unwrap().frames(8), vec![7]);
+/// ```
+pub fn parse_frame_selector(input: &str) -> Result<FrameSelector, GifError> {
+    selector_at(input, 0)
+}
+
+fn selector_at(input: &str, offset: usize) -> Result<FrameSelector, GifError> {
+    let (range, step) = match input.split_once('/') {
+        Some((range, step)) => (range, Some(step)),
+        None => (input, None),
+    };
+    let leading = range.len() - range.trim_start().len();
+    let (range, offset) = (range.trim(), offset + leading);
+    if range.is_empty() {
+        return Err(token_error(input, offset, "Missing frame selector"));
+    }
+
+    let (start, used) = parse_frame_index(range, offset)?;
+    let rest = &range[used..];
+    let end = if rest.is_empty() {
+        Some(start)
+    } else if let Some(after) = rest.strip_prefix('-') {
+        let end_offset = offset + used + 1;
+        if after.is_empty() {
+            None
+        } else {
+            let (end, used) = parse_frame_index(after, end_offset)?;
+            if used < after.len() {
+                return Err(token_error(
+                    &after[used..],
+                    end_offset + used,
+                    "Unexpected text after frame range",
+                ));
+            }
+            Some(end)
+        }
+    } else {
+        return Err(token_error(
+            rest,
+            offset + used,
+            "Unexpected text after frame index (ranges look like 0-9)",
+        ));
+    };
+
+    let backwards = match (start, end) {
+        (FrameIndex::FromStart(a), Some(FrameIndex::FromStart(b))) => a > b,
+        (FrameIndex::FromEnd(a), Some(FrameIndex::FromEnd(b))) => a < b,
+        _ => false,
+    };
+    if backwards {
+        return Err(token_error(range, offset, "Range ends before it starts"));
+    }
+
+    let step = match step {
+        None => 1,
+        Some(_) if end == Some(start) => {
+            return Err(token_error(
+                input,
+                offset - leading,
+                "A step needs a range like 0-20/2",
+            ))
+        }
+        Some(step) => {
+            let step_offset = offset - leading + input.find('/').unwrap_or(0) + 1;
+            match step.trim().parse::<usize>() {
+                Ok(step) if step > 0 => step,
+                _ => {
+                    return Err(token_error(
+                        step,
+                        step_offset,
+                        "Step must be a whole number of at least 1",
+                    ))
+                }
+            }
+        }
+    };
+
+    Ok(FrameSelector { start, end, step })
+}
+
+/// Parses a delay change: `5` sets it, `+5` / `-2` add or subtract
+/// centiseconds, `*1.5` / `/2` scale it, and `2..10`, `2..` or `..10` clamp it.
+///
+/// # Example
+/// ```
+/// use gifmeta::gifmeta_structs::DelayOp;
+/// use gifmeta::utils::parse_csv::parse_delay_op;
+/// assert_eq!(parse_delay_op("+5").unwrap(), DelayOp::Add(5));
+/// assert_eq!(parse_delay_op("*1.5").unwrap().apply(10), 15);
+/// assert_eq!(parse_delay_op("2..").unwrap().apply(0), 2);
+/// ```
+pub fn parse_delay_op(input: &str) -> Result<DelayOp, GifError> {
+    delay_op_at(input, 0)
+}
+
+fn delay_op_at(input: &str, offset: usize) -> Result<DelayOp, GifError> {
+    let leading = input.len() - input.trim_start().len();
+    let (text, offset) = (input.trim(), offset + leading);
+    let centiseconds = |value: &str, offset: usize| {
+        value
+            .trim()
+            .parse::<u16>()
+            .map_err(|_| token_error(value, offset, "Invalid delay (centiseconds, 0-65535)"))
+    };
+    let factor = |value: &str, offset: usize| match value.trim().parse::<f64>() {
+        Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(factor),
+        _ => Err(token_error(
+            value,
+            offset,
+            "Invalid factor (expected a positive number)",
+        )),
+    };
+
+    if let Some((min, max)) = text.split_once("..") {
+        let min = (!min.trim().is_empty())
+            .then(|| centiseconds(min, offset))
+            .transpose()?;
+        let max = (!max.trim().is_empty())
+            .then(|| centiseconds(max, offset + text.find("..").unwrap_or(0) + 2))
+            .transpose()?;
+        return match (min, max) {
+            (None, None) => Err(token_error(
+                text,
+                offset,
+                "A clamp needs a minimum, a maximum or both, like 2..10",
+            )),
+            (Some(min), Some(max)) if min > max => Err(token_error(
+                text,
+                offset,
+                "Clamp minimum is above its maximum",
+            )),
+            (min, max) => Ok(DelayOp::Clamp { min, max }),
+        };
+    }
+    match text.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let change = centiseconds(&text[1..], offset + 1)? as i32;
+            Ok(DelayOp::Add(if sign == '-' { -change } else { change }))
+        }
+        Some('*' | 'x') => Ok(DelayOp::Scale(factor(&text[1..], offset + 1)?)),
+        Some('/') => Ok(DelayOp::Scale(1.0 / factor(&text[1..], offset + 1)?)),
+        _ => Ok(DelayOp::Set(centiseconds(text, offset)?)),
+    }
+}
+
+/// Parses a `--delays` value: comma-separated `SELECTOR=DELAY` entries, with
+/// selectors as in [`parse_frame_selector`] and delays as in [`parse_delay_op`].
+///
+/// Errors name the offending token and its position in `input`.
+///
+/// # Example
+/// ```
+/// use gifmeta::gifmeta_structs::DelayOp;
+/// use gifmeta::utils::parse_csv::parse_frame_delays;
+/// let delays = parse_frame_delays("0-9=5,-1=+200").unwrap();
+/// assert_eq!(delays[1].frames.frames(30), vec![29]);
+/// assert_eq!(delays[1].op, DelayOp::Add(200));
+///
+/// let error = parse_frame_delays("0-9=5,1x=4").unwrap_err();
+/// assert!(error.to_string().contains("at character 8"));
+/// ```
+pub fn parse_frame_delays(input: &str) -> Result<Vec<FrameDelay>, GifError> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    for part in input.split(',') {
+        let (selector, delay) = part
+            .split_once('=')
+            .ok_or_else(|| token_error(part, offset, "Expected SELECTOR=DELAY"))?;
+        let frames = selector_at(selector, offset)?;
+        let op = delay_op_at(delay, offset + selector.len() + 1)?;
+        entries.push(FrameDelay { frames, op });
+        offset += part.len() + 1;
+    }
+    Ok(entries)
+}
+
+/// Parses a duration such as `2.4s`, `2400ms` or `240cs`; a bare number is
+/// in seconds.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use gifmeta::utils::parse_csv::parse_duration;
+/// assert_eq!(parse_duration("2.4s").unwrap(), Duration::from_millis(2400));
+/// assert_eq!(parse_duration("240cs").unwrap(), Duration::from_millis(2400));
+/// ```
+pub fn parse_duration(input: &str) -> Result<Duration, GifError> {
+    let text = input.trim();
+    let (number, seconds_per_unit) = [("ms", 0.001), ("cs", 0.01), ("s", 1.0)]
+        .into_iter()
+        .find_map(|(unit, scale)| text.strip_suffix(unit).map(|n| (n, scale)))
+        .unwrap_or((text, 1.0));
+    let seconds = number.trim().parse::<f64>().map(|n| n * seconds_per_unit);
+    match seconds.ok().map(Duration::try_from_secs_f64) {
+        Some(Ok(duration)) if !duration.is_zero() => Ok(duration),
+        _ => Err(GifError::invalid_spec(
+            input,
+            "Invalid duration (expected a positive number of s, ms or cs, e.g. 2.4s)",
+        )),
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use crate::blocks::{
+    Application, Block, GifFile, Span, EXTENSION_INTRODUCER, LABEL_APPLICATION,
+    LABEL_GRAPHIC_CONTROL,
+};
+use crate::utils::loop_count;
+
+/// A set of byte-level edits applied to an otherwise verbatim copy of a file.
+///
+/// Edits are expressed against offsets in the original data and must not overlap.
+#[derive(Debug, Default)]
+pub struct Patch {
+    edits: Vec<(Span, Vec<u8>)>,
+}
+
+impl Patch {
+    pub fn new() -> Self {
+        Patch::default()
+    }
+
+    /// Replaces the bytes covered by `span` with `bytes`.
+    pub fn replace(&mut self, span: Span, bytes: Vec<u8>) {
+        self.edits.push((span, bytes));
+    }
+
+    /// Inserts `bytes` before the byte at `offset`.
+    pub fn insert(&mut self, offset: usize, bytes: Vec<u8>) {
+        self.edits.push((Span::new(offset, 0), bytes));
+    }
+
+    /// Removes the bytes covered by `span`.
+    pub fn remove(&mut self, span: Span) {
+        self.edits.push((span, Vec::new()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Produces the patched file, copying every untouched byte verbatim.
+    pub fn apply(mut self, data: &[u8]) -> Vec<u8> {
+        // Insertions sort before a replacement starting at the same offset;
+        // the stable sort keeps insertions in the order they were added.
+        self.edits.sort_by_key(|(span, _)| (span.offset, span.len));
+
+        let mut out = Vec::with_capacity(data.len());
+        let mut pos = 0;
+        for (span, bytes) in self.edits {
+            out.extend_from_slice(&data[pos..span.offset]);
+            out.extend_from_slice(&bytes);
+            pos = span.end();
+        }
+        out.extend_from_slice(&data[pos..]);
+        out
+    }
+}
+
+/// What to do with the loop count when patching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoopEdit {
+    /// Leave any loop count untouched.
+    #[default]
+    Keep,
+    /// Delete every NETSCAPE2.0 / ANIMEXTS1.0 block.
+    Remove,
+    /// Store this raw repeat count (0 = infinite), inserting a block if needed.
+    Set(u16),
+}
+
+/// All changes to make to the loop extension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoopChanges {
+    pub edit: LoopEdit,
+    /// Buffer size to store in the NETSCAPE `0x02` sub-block.
+    pub buffer_size: Option<u32>,
+    /// Rewrite ANIMEXTS1.0 as NETSCAPE2.0 and drop duplicate loop extensions.
+    pub normalize: bool,
+}
+
+impl From<LoopEdit> for LoopChanges {
+    fn from(edit: LoopEdit) -> Self {
+        LoopChanges {
+            edit,
+            ..Default::default()
+        }
+    }
+}
+
+/// The `0x03 0x01 <lo> <hi>` loop sub-block.
+fn loop_sub_block(repeat: u16) -> Vec<u8> {
+    let mut sub = vec![0x03, 0x01];
+    sub.extend_from_slice(&repeat.to_le_bytes());
+    sub
+}
+
+/// The `0x05 0x02 <u32>` buffering sub-block.
+fn buffer_sub_block(size: u32) -> Vec<u8> {
+    let mut sub = vec![0x05, 0x02];
+    sub.extend_from_slice(&size.to_le_bytes());
+    sub
+}
+
+/// Re-emits an existing loop extension with the requested changes.
+///
+/// Sub-blocks that are not being changed are copied verbatim, so an edit that
+/// only touches the repeat count differs from the original in those two bytes.
+fn rebuild_loop_block(gif: &GifFile, app: &Application, changes: &LoopChanges) -> Vec<u8> {
+    let mut block = vec![EXTENSION_INTRODUCER, LABEL_APPLICATION];
+    if changes.normalize && app.identifier != "NETSCAPE2.0" {
+        block.push(0x0B);
+        block.extend_from_slice(b"NETSCAPE2.0");
+    } else {
+        block.extend_from_slice(&gif.data[app.header.offset - 1..app.header.end()]);
+    }
+
+    let mut wrote_loop = false;
+    let mut wrote_buffer = false;
+    for chunk in &app.data.chunks {
+        match (gif.data[chunk.offset], changes.edit, changes.buffer_size) {
+            (0x01, LoopEdit::Set(repeat), _) if !wrote_loop => {
+                block.extend(loop_sub_block(repeat));
+                wrote_loop = true;
+            }
+            (0x02, _, Some(size)) if !wrote_buffer => {
+                block.extend(buffer_sub_block(size));
+                wrote_buffer = true;
+            }
+            _ => block.extend_from_slice(&gif.data[chunk.offset - 1..chunk.end()]),
+        }
+    }
+    if let (LoopEdit::Set(repeat), false) = (changes.edit, wrote_loop) {
+        block.extend(loop_sub_block(repeat));
+    }
+    if let (Some(size), false) = (changes.buffer_size, wrote_buffer) {
+        block.extend(buffer_sub_block(size));
+    }
+    block.push(0x00);
+    block
+}
+
+/// Builds a minimal Graphic Control Extension carrying only a delay.
+pub fn graphic_control_block(delay_cs: u16) -> Vec<u8> {
+    let mut block = vec![EXTENSION_INTRODUCER, LABEL_GRAPHIC_CONTROL, 0x04, 0x00];
+    block.extend_from_slice(&delay_cs.to_le_bytes());
+    block.extend_from_slice(&[0x00, 0x00]);
+    block
+}
+
+/// Offset right after the header, logical screen and global palette,
+/// which is where a loop extension conventionally lives.
+pub fn after_screen(gif: &GifFile) -> usize {
+    gif.global_palette
+        .as_ref()
+        .map(|p| p.span.end())
+        .unwrap_or(gif.screen.span.end())
+}
+
+/// Offset of the first frame (its Graphic Control Extension if it has one),
+/// which is where file-level metadata blocks such as comments are added.
+pub fn before_first_frame(gif: &GifFile) -> usize {
+    gif.blocks
+        .iter()
+        .find(|b| matches!(b, Block::GraphicControl(_) | Block::Image(_)))
+        .map(|b| b.span().offset)
+        .or(gif.trailer)
+        .unwrap_or(gif.data.len())
+}
+
+/// Rewrites loop count and frame delays without touching image data.
+///
+/// Only the loop extension and the delay field of each Graphic Control
+/// Extension are changed. The first NETSCAPE2.0 / ANIMEXTS1.0 block receives
+/// the new repeat count and buffer size (a NETSCAPE2.0 block is inserted after
+/// the global palette if none exists), or every loop block is removed with
+/// [`LoopEdit::Remove`]. A Graphic Control Extension is inserted before any
+/// frame that needs a delay but has none. Every other byte is copied as-is.
+///
+/// # Arguments
+/// - `gif`: Parsed input file
+/// - `loop_changes`: What to do with the loop extension
+/// - `delays`: New delay (centiseconds) per 0-based frame index
+pub fn patch_metadata(
+    gif: &GifFile,
+    loop_changes: LoopChanges,
+    delays: &HashMap<usize, u16>,
+) -> Vec<u8> {
+    let mut patch = Patch::new();
+    metadata_edits(gif, &mut patch, loop_changes, delays);
+    patch.apply(&gif.data)
+}
+
+/// Adds the loop and delay edits described in [`patch_metadata`] to `patch`.
+pub fn metadata_edits(
+    gif: &GifFile,
+    patch: &mut Patch,
+    loop_changes: LoopChanges,
+    delays: &HashMap<usize, u16>,
+) {
+    let loop_apps: Vec<&Application> = gif
+        .applications()
+        .filter(|app| loop_count::LOOP_EXTENSIONS.contains(&app.identifier.as_str()))
+        .collect();
+
+    if loop_changes.edit == LoopEdit::Remove {
+        for app in &loop_apps {
+            patch.remove(app.span);
+        }
+    } else if loop_changes != LoopChanges::default() {
+        match loop_apps.split_first() {
+            Some((first, rest)) => {
+                patch.replace(first.span, rebuild_loop_block(gif, first, &loop_changes));
+                if loop_changes.normalize {
+                    for app in rest {
+                        patch.remove(app.span);
+                    }
+                }
+            }
+            None if loop_changes.edit != LoopEdit::Keep || loop_changes.buffer_size.is_some() => {
+                let mut block = vec![EXTENSION_INTRODUCER, LABEL_APPLICATION, 0x0B];
+                block.extend_from_slice(b"NETSCAPE2.0");
+                if let LoopEdit::Set(repeat) = loop_changes.edit {
+                    block.extend(loop_sub_block(repeat));
+                }
+                if let Some(size) = loop_changes.buffer_size {
+                    block.extend(buffer_sub_block(size));
+                }
+                block.push(0x00);
+                patch.insert(after_screen(gif), block);
+            }
+            // Nothing to normalize
+            None => {}
+        }
+    }
+
+    let mut index = 0;
+    let mut pending_gce = None;
+    for block in &gif.blocks {
+        match block {
+            Block::GraphicControl(gce) => pending_gce = Some(gce),
+            Block::Image(img) => {
+                if let Some(&delay) = delays.get(&index) {
+                    match pending_gce.take() {
+                        Some(gce) if gce.delay_cs != delay => patch
+                            .replace(Span::new(gce.delay_offset, 2), delay.to_le_bytes().to_vec()),
+                        Some(_) => {}
+                        None => patch.insert(img.span.offset, graphic_control_block(delay)),
+                    }
+                }
+                pending_gce = None;
+                index += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Splits `data` into a chain of data sub-blocks (at most 255 bytes each),
+/// including the zero-length terminator.
+pub fn sub_blocks(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / 255 + 2);
+    for chunk in data.chunks(255) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+    out.push(0x00);
+    out
+}
@@ -0,0 +1,129 @@
+use image::{Rgba, RgbaImage};
+
+use crate::blocks::{Block, GifFile, GraphicControl, PlainText};
+use crate::gifmeta_structs::PlainTextMeta;
+use crate::utils::bitmap_font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+/// A Plain Text Extension together with its Graphic Control Extension and
+/// the number of images that precede it in the stream.
+pub struct PlainTextBlock<'a> {
+    pub images_before: usize,
+    pub control: Option<&'a GraphicControl>,
+    pub text: &'a PlainText,
+}
+
+/// Lists every Plain Text Extension in file order.
+pub fn plain_text_blocks(gif: &GifFile) -> Vec<PlainTextBlock<'_>> {
+    let mut found = Vec::new();
+    let mut images = 0;
+    let mut pending = None;
+    for block in &gif.blocks {
+        match block {
+            Block::GraphicControl(gce) => pending = Some(gce),
+            Block::PlainText(text) => found.push(PlainTextBlock {
+                images_before: images,
+                control: pending.take(),
+                text,
+            }),
+            Block::Image(_) => {
+                pending = None;
+                images += 1;
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Decodes every Plain Text Extension.
+///
+/// Each one is reported with the frame it is drawn over: the closest image
+/// before it, or the first frame for text that precedes every image.
+pub fn read_plain_text(gif: &GifFile) -> Vec<PlainTextMeta> {
+    plain_text_blocks(gif)
+        .into_iter()
+        .map(|block| {
+            let text = block.text;
+            PlainTextMeta {
+                offset: text.span.offset,
+                frame: block.images_before.saturating_sub(1),
+                left: text.left,
+                top: text.top,
+                width: text.width,
+                height: text.height,
+                cell_width: text.cell_width,
+                cell_height: text.cell_height,
+                foreground_index: text.foreground_index,
+                background_index: text.background_index,
+                delay_cs: block.control.map(|gce| gce.delay_cs),
+                transparent_index: block.control.and_then(|gce| gce.transparent_index),
+                text: String::from_utf8_lossy(&text.data.collect(&gif.data)).into_owned(),
+            }
+        })
+        .collect()
+}
+
+/// Looks up a global palette entry, falling back to `fallback` when the file
+/// has no global palette or the index lies beyond it.
+fn palette_color(gif: &GifFile, index: u8, fallback: [u8; 3]) -> Rgba<u8> {
+    let rgb = gif
+        .global_palette
+        .as_ref()
+        .and_then(|palette| {
+            let start = palette.span.offset + index as usize * 3;
+            gif.data
+                .get(start..start + 3)
+                .filter(|_| (index as usize) < palette.entries)
+        })
+        .map_or(fallback, |rgb| [rgb[0], rgb[1], rgb[2]]);
+    Rgba([rgb[0], rgb[1], rgb[2], 255])
+}
+
+/// Rasterizes a Plain Text Extension onto `canvas` with the built-in font.
+///
+/// Characters fill the text grid left to right, top to bottom, each glyph
+/// scaled to the cell size; anything past the last cell is dropped.
+/// Colors come from the global palette, and a color matching the
+/// transparent index of the block's Graphic Control Extension is skipped.
+pub fn render_plain_text(canvas: &mut RgbaImage, gif: &GifFile, block: &PlainTextBlock) {
+    let text = block.text;
+    if text.cell_width == 0 || text.cell_height == 0 {
+        return;
+    }
+    let transparent = block.control.and_then(|gce| gce.transparent_index);
+    let foreground = (transparent != Some(text.foreground_index))
+        .then(|| palette_color(gif, text.foreground_index, [255, 255, 255]));
+    let background = (transparent != Some(text.background_index))
+        .then(|| palette_color(gif, text.background_index, [0, 0, 0]));
+
+    let (cell_w, cell_h) = (text.cell_width as u32, text.cell_height as u32);
+    let columns = text.width as u32 / cell_w;
+    let rows = text.height as u32 / cell_h;
+    let mut put = |x: u32, y: u32, color: Rgba<u8>| {
+        let (cx, cy) = (text.left as u32 + x, text.top as u32 + y);
+        if cx < canvas.width() && cy < canvas.height() {
+            canvas.put_pixel(cx, cy, color);
+        }
+    };
+
+    if let Some(color) = background {
+        for y in 0..text.height as u32 {
+            for x in 0..text.width as u32 {
+                put(x, y, color);
+            }
+        }
+    }
+
+    let Some(color) = foreground else { return };
+    let chars = text.data.collect(&gif.data);
+    for (i, &byte) in chars.iter().enumerate().take((columns * rows) as usize) {
+        let (col, row) = (i as u32 % columns, i as u32 / columns);
+        for py in 0..cell_h {
+            for px in 0..cell_w {
+                if bitmap_font::pixel(byte, px * GLYPH_WIDTH / cell_w, py * GLYPH_HEIGHT / cell_h) {
+                    put(col * cell_w + px, row * cell_h + py, color);
+                }
+            }
+        }
+    }
+}
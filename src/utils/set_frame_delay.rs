@@ -1,62 +1,31 @@
-use gif::{DecodeOptions, Encoder};
-use std::fs;
-use std::fs::File;
-use std::io::{BufReader, BufWriter, Write};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use tempfile::NamedTempFile;
+
+use crate::blocks;
+use crate::gifmeta_structs::GifError;
+use crate::utils::in_place;
+use crate::utils::patch::{self, LoopEdit};
 
 /// Applies a fixed delay (in centiseconds) to all frames in a GIF.
 ///
+/// Only the delay fields of the Graphic Control Extensions are rewritten;
+/// image data is copied byte for byte.
+///
 /// # Arguments
 /// - `path`: Path to the input GIF file.
 /// - `delay`: Delay to apply to each frame (centiseconds).
-/// - `output`: Optional output path. If None, overwrites the input file.
-pub fn set_frame_delay(input: &PathBuf, delay: u16, output: Option<PathBuf>) -> Result<(), String> {
-    // Open input file
-    let file = File::open(input).map_err(|e| format!("Failed to open input: {}", e))?;
-    let mut decoder = DecodeOptions::new();
-    decoder.set_color_output(gif::ColorOutput::Indexed);
-    let mut reader = decoder
-        .read_info(BufReader::new(file))
-        .map_err(|e| format!("Decode error: {}", e))?;
-
-    // Determine output path
-    let (mut writer, out_path): (BufWriter<Box<dyn Write>>, PathBuf) = match output {
-        Some(ref path) => {
-            let file = File::create(path).map_err(|e| format!("Failed to create output: {}", e))?;
-            (BufWriter::new(Box::new(file)), path.clone())
-        }
-        None => {
-            let tmp = NamedTempFile::new().map_err(|e| format!("Tempfile error: {}", e))?;
-            let path = tmp.path().to_path_buf();
-            (BufWriter::new(Box::new(tmp)), path)
-        }
-    };
-
-    // Encode
-    let mut encoder = Encoder::new(
-        &mut writer,
-        reader.width(),
-        reader.height(),
-        reader.global_palette().unwrap_or(&[]),
-    )
-    .map_err(|e| format!("Encoder init error: {}", e))?;
+/// - `output`: Optional output path. If None, the input file is replaced atomically.
+pub fn set_frame_delay(
+    input: &PathBuf,
+    delay: u16,
+    output: Option<PathBuf>,
+) -> Result<(), GifError> {
+    let gif = blocks::read_file(input)?;
 
-    while let Some(frame) = reader
-        .read_next_frame()
-        .map_err(|e| format!("Frame read error: {}", e))?
-    {
-        let mut owned = frame.clone();
-        owned.delay = delay;
-        encoder
-            .write_frame(&owned)
-            .map_err(|e| format!("Frame write error: {}", e))?;
-    }
+    let delays: HashMap<usize, u16> = (0..gif.images().count()).map(|i| (i, delay)).collect();
+    let data = patch::patch_metadata(&gif, LoopEdit::Keep.into(), &delays);
 
-    // If using a temp file, replace the original input
-    if output.is_none() {
-        fs::rename(&out_path, input).map_err(|e| format!("Failed to overwrite original: {}", e))?;
-    }
+    let out_path = in_place::write_or_replace(input, output.as_deref(), &data)?;
 
     println!(
         "✅ Frame delay of {}cs applied to all frames → {}",
@@ -66,3 +35,33 @@ pub fn set_frame_delay(input: &PathBuf, delay: u16, output: Option<PathBuf>) ->
 
     Ok(())
 }
+
+/// Multiplies every delay by `factor`, rounding to whole centiseconds while
+/// keeping the total as close as possible to the exact scaled total.
+///
+/// Rounding each frame on its own can drift by up to half a centisecond per
+/// frame; instead each frame ends where the rounded running total says it
+/// should, so the rounding error is carried into the next frame and the new
+/// total is the exact total rounded once.
+///
+/// # Example
+/// ```
+/// use gifmeta::utils::set_frame_delay::scale_delays;
+/// // 10 frames of 4 cs played 1.5× as fast last 26.7 cs: 27 cs, not 10 × 3 cs
+/// let delays = scale_delays(&[4; 10], 1.0 / 1.5);
+/// assert_eq!(delays, [3, 2, 3, 3, 2, 3, 3, 2, 3, 3]);
+/// assert_eq!(delays.iter().sum::<u16>(), 27);
+/// ```
+pub fn scale_delays(delays: &[u16], factor: f64) -> Vec<u16> {
+    let mut exact_total = 0.0;
+    let mut written_total = 0.0;
+    delays
+        .iter()
+        .map(|&delay| {
+            exact_total += delay as f64 * factor;
+            let delay = (exact_total.round() - written_total).clamp(0.0, u16::MAX as f64);
+            written_total += delay;
+            delay as u16
+        })
+        .collect()
+}
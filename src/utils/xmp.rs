@@ -0,0 +1,106 @@
+use crate::blocks::{Application, GifFile, EXTENSION_INTRODUCER, LABEL_APPLICATION};
+use crate::gifmeta_structs::GifError;
+use crate::utils::patch::{self, Patch};
+
+/// Application identifier used by Adobe tools for XMP packets.
+pub const XMP_IDENTIFIER: &str = "XMP DataXMP";
+
+/// Length of the "magic trailer", including the block terminator.
+pub const MAGIC_TRAILER_LEN: usize = 258;
+
+/// The magic trailer: `0x01`, then `0xFF` down to `0x00`, then the block terminator.
+///
+/// XMP is stored raw rather than as sub-blocks, so a decoder that treats the
+/// packet bytes as sub-block sizes lands somewhere on this ramp and is walked
+/// down to the terminator.
+pub fn magic_trailer() -> Vec<u8> {
+    let mut trailer = Vec::with_capacity(MAGIC_TRAILER_LEN);
+    trailer.push(0x01);
+    trailer.extend((0..=0xFFu8).rev());
+    trailer.push(0x00);
+    trailer
+}
+
+/// Iterates over XMP application extensions in file order.
+pub fn xmp_blocks(gif: &GifFile) -> impl Iterator<Item = &Application> {
+    gif.applications()
+        .filter(|app| app.identifier == XMP_IDENTIFIER)
+}
+
+/// Extracts the raw XMP packet from the first XMP application extension.
+pub fn read_xmp_bytes(gif: &GifFile) -> Result<Option<Vec<u8>>, GifError> {
+    xmp_blocks(gif)
+        .next()
+        .map(|app| xmp_packet(gif, app))
+        .transpose()
+}
+
+/// Extracts the raw XMP packet stored in `app`.
+///
+/// The packet ends where the magic trailer starts. If the trailer is damaged,
+/// the packet is cut after its `<?xpacket end=...?>` processing instruction.
+pub fn xmp_packet(gif: &GifFile, app: &Application) -> Result<Vec<u8>, GifError> {
+    let raw = gif.bytes(app.data.span);
+
+    if raw.ends_with(&magic_trailer()) {
+        return Ok(raw[..raw.len() - MAGIC_TRAILER_LEN].to_vec());
+    }
+
+    let end_marker = b"<?xpacket end=";
+    let packet_end = raw
+        .windows(end_marker.len())
+        .rposition(|w| w == end_marker)
+        .and_then(|start| {
+            raw[start..]
+                .windows(2)
+                .position(|w| w == b"?>")
+                .map(|pos| start + pos + 2)
+        });
+    match packet_end {
+        Some(end) => Ok(raw[..end].to_vec()),
+        None => Err(GifError::decode(
+            app.span.offset,
+            "XMP extension has neither a magic trailer nor an xpacket end marker",
+        )),
+    }
+}
+
+/// Extracts the XMP packet as XML text.
+pub fn read_xmp(gif: &GifFile) -> Result<Option<String>, GifError> {
+    match read_xmp_bytes(gif)? {
+        Some(bytes) => String::from_utf8(bytes).map(Some).map_err(|_| {
+            let offset = xmp_blocks(gif).next().map_or(0, |app| app.span.offset);
+            GifError::decode(offset, "XMP packet is not valid UTF-8")
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Builds an XMP application extension with a correct magic trailer.
+pub fn xmp_block(xml: &[u8]) -> Vec<u8> {
+    let mut block = vec![EXTENSION_INTRODUCER, LABEL_APPLICATION, 0x0B];
+    block.extend_from_slice(XMP_IDENTIFIER.as_bytes());
+    block.extend_from_slice(xml);
+    block.extend(magic_trailer());
+    block
+}
+
+/// Adds XMP edits to `patch`.
+///
+/// `replace` rewrites the first XMP block (or adds one before the first frame)
+/// and drops any others; `strip` removes every XMP block.
+pub fn xmp_edits(gif: &GifFile, patch: &mut Patch, replace: Option<&[u8]>, strip: bool) {
+    let existing: Vec<_> = xmp_blocks(gif).map(|app| app.span).collect();
+
+    if let Some(xml) = replace {
+        let position = existing
+            .first()
+            .map_or_else(|| patch::before_first_frame(gif), |s| s.offset);
+        patch.insert(position, xmp_block(xml));
+    }
+    if replace.is_some() || strip {
+        for span in existing {
+            patch.remove(span);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use gifmeta::blocks;
+use gifmeta::GifError;
+
+#[test]
+fn test_blocks_cover_every_byte() {
+    for path in [
+        "tests/testdata/loop/1.gif",
+        "tests/testdata/loop/2frame-soft.gif",
+        "tests/testdata/layout/offset-frames.gif",
+        "tests/testdata/transparency/stars-transparent.gif",
+    ] {
+        let gif = blocks::read_file(path).unwrap();
+
+        let mut expected = gif.screen.span.end();
+        if let Some(palette) = &gif.global_palette {
+            assert_eq!(palette.span.offset, expected, "{}: palette offset", path);
+            expected = palette.span.end();
+        }
+        for block in &gif.blocks {
+            assert_eq!(block.span().offset, expected, "{}: gap before block", path);
+            expected = block.span().end();
+        }
+        assert_eq!(gif.trailer, Some(expected), "{}: trailer offset", path);
+        assert_eq!(expected + 1, gif.data.len(), "{}: trailing bytes", path);
+    }
+}
+
+#[test]
+fn test_blocks_match_decoder_frames() {
+    let path = "tests/testdata/timing/variable-delays.gif";
+    let gif = blocks::read_file(path).unwrap();
+    let meta = gifmeta::get_metadata(std::path::Path::new(path), true).unwrap();
+
+    let frames = gif.frames();
+    assert_eq!(frames.len(), meta.frame_count as usize);
+    for ((gce, _), frame) in frames.iter().zip(&meta.frames) {
+        assert_eq!(gce.unwrap().delay_cs, frame.delay_cs);
+    }
+}
+
+#[test]
+fn test_blocks_find_netscape_extension() {
+    let gif = blocks::read_file("tests/testdata/loop/no-loop-flag.gif").unwrap();
+    let app = gif.applications().next().expect("NETSCAPE block");
+
+    assert_eq!(app.identifier, "NETSCAPE2.0");
+    assert_eq!(app.span.offset, 0x13);
+    assert_eq!(gif.bytes(app.data.chunks[0]), &[0x01, 0x00, 0x00]);
+}
+
+#[test]
+fn test_blocks_reject_truncated_file() {
+    let data = std::fs::read("tests/testdata/loop/2frame-soft.gif").unwrap();
+    let cut = data[..data.len() - 10].to_vec();
+    match blocks::parse(cut) {
+        Err(GifError::Decode {
+            offset: Some(offset),
+            ..
+        }) => assert!(offset > 0 && offset < data.len() - 10),
+        other => panic!("Expected decode error, got {:?}", other),
+    }
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use gifmeta::get_comments;
+use gifmeta::gifmeta_structs::ModifyOptions;
+
+#[test]
+fn test_info_lists_comments() {
+    let meta = gifmeta::get_metadata(Path::new("tests/testdata/loop/2.gif"), false).unwrap();
+    assert_eq!(meta.comments.len(), 1);
+    assert_eq!(meta.comments[0].text, "Created with GIMP");
+    assert_eq!(meta.comments[0].bytes, b"Created with GIMP");
+    assert_eq!(meta.comments[0].offset, 800);
+}
+
+#[test]
+fn test_replace_append_and_clear_comments() {
+    let output = Path::new("tests/testdata/loop/2-comments.gif");
+
+    common::modify(
+        "tests/testdata/loop/2.gif",
+        output,
+        ModifyOptions {
+            comment: Some("© Sacred Tools".into()),
+            append_comments: vec!["second".into()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let texts: Vec<String> = get_comments(output)
+        .unwrap()
+        .into_iter()
+        .map(|c| c.text)
+        .collect();
+    assert_eq!(texts, vec!["© Sacred Tools", "second"]);
+
+    common::modify(
+        "tests/testdata/loop/2.gif",
+        output,
+        ModifyOptions {
+            append_comments: vec!["credit".into()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let texts: Vec<String> = get_comments(output)
+        .unwrap()
+        .into_iter()
+        .map(|c| c.text)
+        .collect();
+    assert_eq!(texts, vec!["Created with GIMP", "credit"]);
+
+    common::modify(
+        "tests/testdata/loop/2.gif",
+        output,
+        ModifyOptions {
+            clear_comments: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(get_comments(output).unwrap().is_empty());
+    assert_eq!(
+        fs::read("tests/testdata/loop/2.gif").unwrap().len() - fs::read(output).unwrap().len(),
+        21
+    );
+
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_long_comment_is_split_into_sub_blocks() {
+    let output = Path::new("tests/testdata/loop/2frame-soft-long-comment.gif");
+    let text = "gifmeta ".repeat(100);
+
+    common::modify(
+        "tests/testdata/loop/2frame-soft.gif",
+        output,
+        ModifyOptions {
+            comment: Some(text.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let gif = gifmeta::blocks::read_file(output).unwrap();
+    let comment = gif
+        .blocks
+        .iter()
+        .find_map(|b| match b {
+            gifmeta::blocks::Block::Comment(c) => Some(c),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(comment.data.chunks.len(), 4);
+    assert_eq!(get_comments(output).unwrap()[0].text, text);
+
+    // Frames still decode after the inserted block
+    let meta = gifmeta::get_metadata(output, true).unwrap();
+    assert_eq!(meta.frame_count, 2);
+
+    fs::remove_file(output).unwrap();
+}
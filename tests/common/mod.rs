@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use gifmeta::gifmeta_structs::ModifyOptions;
+use gifmeta::{mod_gif_with_options, GifError};
+
+/// Applies `options` to `input`, writing the result to `output`.
+#[allow(dead_code)]
+pub fn modify(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    options: ModifyOptions,
+) -> Result<(), GifError> {
+    let input = input.as_ref().to_path_buf();
+    mod_gif_with_options(&input, Some(output.as_ref().to_path_buf()), &options).map(|_| ())
+}
+
+/// Builds a minimal ICC v2 profile with a `desc` tag, padded to `size` bytes.
+#[allow(dead_code)]
+pub fn profile(description: &str, size: usize) -> Vec<u8> {
+    let mut tag = b"desc\0\0\0\0".to_vec();
+    tag.extend_from_slice(&(description.len() as u32 + 1).to_be_bytes());
+    tag.extend_from_slice(description.as_bytes());
+    tag.push(0);
+
+    let tag_offset = 128 + 4 + 12;
+    let mut data = vec![0u8; 128];
+    data[36..40].copy_from_slice(b"acsp");
+    data.extend_from_slice(&1u32.to_be_bytes());
+    data.extend_from_slice(b"desc");
+    data.extend_from_slice(&(tag_offset as u32).to_be_bytes());
+    data.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+    data.extend_from_slice(&tag);
+    data.resize(size.max(data.len()), 0);
+    let len = data.len() as u32;
+    data[0..4].copy_from_slice(&len.to_be_bytes());
+    data
+}
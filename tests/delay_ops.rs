@@ -0,0 +1,76 @@
+use std::fs;
+
+use gifmeta::gifmeta_structs::{DelayOp, FrameDelay, FrameSelector, GifError, ModifyOptions};
+use gifmeta::utils::parse_csv::{parse_delay_op, parse_frame_delays};
+
+/// variable-delays.gif has delays of 20, 100 and 50 cs.
+fn delays_after(options: &ModifyOptions) -> Vec<u16> {
+    let data = fs::read("tests/testdata/timing/variable-delays.gif").unwrap();
+    let (modified, _) = gifmeta::mod_gif_from_bytes(&data, options).unwrap();
+    let meta = gifmeta::get_metadata_from_bytes(&modified, true).unwrap();
+    meta.frames.iter().map(|f| f.delay_cs).collect()
+}
+
+#[test]
+fn test_parse_delay_ops() {
+    assert_eq!(parse_delay_op("12").unwrap(), DelayOp::Set(12));
+    assert_eq!(parse_delay_op("+5").unwrap(), DelayOp::Add(5));
+    assert_eq!(parse_delay_op(" -2 ").unwrap(), DelayOp::Add(-2));
+    assert_eq!(parse_delay_op("*1.5").unwrap(), DelayOp::Scale(1.5));
+    assert_eq!(parse_delay_op("/4").unwrap(), DelayOp::Scale(0.25));
+    assert_eq!(
+        parse_delay_op("2..10").unwrap(),
+        DelayOp::Clamp {
+            min: Some(2),
+            max: Some(10)
+        }
+    );
+    assert_eq!(
+        parse_delay_op("..10").unwrap(),
+        DelayOp::Clamp {
+            min: None,
+            max: Some(10)
+        }
+    );
+
+    for bad in ["+", "*-1", "/0", "..", "9..3", "2..x", "70000"] {
+        assert!(
+            matches!(parse_delay_op(bad), Err(GifError::InvalidSpec { .. })),
+            "{bad:?} should not parse"
+        );
+    }
+
+    // Results stay within what a GIF can store
+    assert_eq!(DelayOp::Add(-50).apply(20), 0);
+    assert_eq!(DelayOp::Add(100).apply(65500), u16::MAX);
+    assert_eq!(DelayOp::Scale(1000.0).apply(100), u16::MAX);
+    assert_eq!(DelayOp::Scale(0.5).apply(5), 3);
+}
+
+#[test]
+fn test_relative_changes_stack_in_order() {
+    let options = ModifyOptions {
+        frame_delays: vec![
+            FrameDelay {
+                frames: FrameSelector::all(),
+                op: DelayOp::Scale(0.5),
+            },
+            FrameDelay {
+                frames: FrameSelector::all(),
+                op: DelayOp::Clamp {
+                    min: Some(15),
+                    max: Some(40),
+                },
+            },
+        ],
+        ..Default::default()
+    };
+    assert_eq!(delays_after(&options), [15, 40, 25]);
+
+    let options = ModifyOptions {
+        delay_all: Some(10),
+        frame_delays: parse_frame_delays("1-=+5,last=*3,0=-20").unwrap(),
+        ..Default::default()
+    };
+    assert_eq!(delays_after(&options), [0, 15, 45]);
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::Path;
+
+use gifmeta::blocks;
+use gifmeta::gifmeta_structs::{DiffOptions, GifDiff, ModifyOptions};
+use gifmeta::{diff_gifs, diff_gifs_from_bytes, mod_gif_from_bytes};
+
+const INPUT: &str = "tests/testdata/loop/2.gif";
+
+fn paths(diff: &GifDiff) -> Vec<&str> {
+    diff.differences.iter().map(|d| d.path.as_str()).collect()
+}
+
+#[test]
+fn test_identical_files() {
+    let options = DiffOptions {
+        compare_pixels: true,
+    };
+    let diff = diff_gifs(Path::new(INPUT), Path::new(INPUT), &options).unwrap();
+    assert!(diff.is_identical());
+    assert!(diff.pixels_compared);
+}
+
+#[test]
+fn test_metadata_differences() {
+    let original = fs::read(INPUT).unwrap();
+    let options = ModifyOptions {
+        loop_count: Some(0),
+        delays: Some([(1, 9)].into_iter().collect()),
+        comment: Some("v2".to_string()),
+        ..Default::default()
+    };
+    let (mut modified, _) = mod_gif_from_bytes(&original, &options).unwrap();
+    // Disposal "restore to background" on the first frame's GCE
+    let gce = blocks::parse(modified.clone()).unwrap().frames()[0]
+        .0
+        .unwrap()
+        .span
+        .offset;
+    modified[gce + 3] = (modified[gce + 3] & !0b0001_1100) | (2 << 2);
+
+    let diff = diff_gifs_from_bytes(&original, &modified, &DiffOptions::default()).unwrap();
+    assert_eq!(
+        paths(&diff),
+        [
+            "total_duration_cs",
+            "loop_count",
+            "frames[0].disposal",
+            "frames[1].delay_cs",
+            "comments"
+        ]
+    );
+    let delay = &diff.differences[3];
+    assert_eq!(
+        (delay.left.as_u64(), delay.right.as_u64()),
+        (Some(3), Some(9))
+    );
+    assert_eq!(diff.differences[4].right, serde_json::json!(["v2"]));
+}
+
+#[test]
+fn test_palette_and_pixel_differences() {
+    let original = fs::read(INPUT).unwrap();
+    let mut recolored = original.clone();
+    // Invert every global palette color except the transparent index 255
+    for byte in &mut recolored[13..13 + 255 * 3] {
+        *byte = !*byte;
+    }
+
+    let metadata_only =
+        diff_gifs_from_bytes(&original, &recolored, &DiffOptions::default()).unwrap();
+    assert_eq!(paths(&metadata_only), ["global_palette"]);
+    assert_eq!(
+        metadata_only.differences[0].note.as_deref(),
+        Some("255 of 256 colors differ")
+    );
+
+    let options = DiffOptions {
+        compare_pixels: true,
+    };
+    let diff = diff_gifs_from_bytes(&original, &recolored, &options).unwrap();
+    assert_eq!(
+        paths(&diff),
+        ["global_palette", "frames[0].pixels", "frames[1].pixels"]
+    );
+}
@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::Path;
+
+use gifmeta::gifmeta_structs::{ModifyOptions, PlayCount};
+use gifmeta::{mod_gif_from_bytes, plan_mod_gif, plan_mod_gif_from_bytes};
+
+const INPUT: &str = "tests/testdata/loop/2.gif";
+
+#[test]
+fn test_plan_lists_loop_and_delay_changes() {
+    let options = ModifyOptions {
+        loop_count: Some(0),
+        delays: Some([(1, 12)].into_iter().collect()),
+        ..Default::default()
+    };
+    let before = fs::read(INPUT).unwrap();
+    let plan = plan_mod_gif(Path::new(INPUT), &options).unwrap();
+
+    let change = plan.loop_count.as_ref().unwrap();
+    assert_eq!(change.old, Some(PlayCount::Times(6)));
+    assert_eq!(change.new, Some(PlayCount::Infinite));
+    assert_eq!(plan.delays.len(), 1);
+    assert_eq!(
+        (
+            plan.delays[0].frame,
+            plan.delays[0].old_cs,
+            plan.delays[0].new_cs
+        ),
+        (1, 3, 12)
+    );
+
+    // The repeat field is patched in place, so the loop block is rewritten, not replaced
+    assert_eq!(plan.changed.len(), 1);
+    assert_eq!(plan.changed[0].before.offset, 781);
+    assert!(plan.added.is_empty() && plan.removed.is_empty());
+    assert_eq!(fs::read(INPUT).unwrap(), before);
+}
+
+#[test]
+fn test_plan_matches_real_run() {
+    let data = fs::read(INPUT).unwrap();
+    let options = ModifyOptions {
+        remove_loop: true,
+        clear_comments: true,
+        append_comments: vec!["reviewed".to_string()],
+        ..Default::default()
+    };
+    let plan = plan_mod_gif_from_bytes(&data, &options).unwrap();
+    let (output, _) = mod_gif_from_bytes(&data, &options).unwrap();
+
+    assert_eq!(plan.original_size, data.len());
+    assert_eq!(plan.output_size, output.len());
+    let removed: Vec<_> = plan.removed.iter().map(|b| b.offset).collect();
+    assert_eq!(removed, [781]);
+    // The old comment gives way to the new one
+    assert!(plan.added.is_empty());
+    assert_eq!(plan.changed.len(), 1);
+    assert_eq!(plan.changed[0].before.kind, "comment");
+    assert_eq!(plan.changed[0].after.len, 3 + 8 + 1);
+    assert!(plan.loop_count.is_some() && plan.delays.is_empty());
+    assert!(plan.dropped.is_empty());
+}
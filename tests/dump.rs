@@ -0,0 +1,93 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use gifmeta::utils::{icc, xmp};
+use gifmeta::{dump_blocks, dump_blocks_from_bytes};
+
+#[test]
+fn test_dump_lists_every_block_in_order() {
+    let path = Path::new("tests/testdata/loop/2.gif");
+    let blocks = dump_blocks(path, false).unwrap();
+
+    let kinds: Vec<_> = blocks.iter().map(|b| b.kind.as_str()).collect();
+    assert_eq!(
+        kinds,
+        [
+            "header",
+            "logical_screen",
+            "global_palette",
+            "application",
+            "comment",
+            "graphic_control",
+            "image",
+            "graphic_control",
+            "image",
+            "trailer",
+        ]
+    );
+
+    // Entries tile the whole file without gaps
+    let mut offset = 0;
+    for block in &blocks {
+        assert_eq!(block.offset, offset, "gap before {}", block.kind);
+        assert!(block.hex.is_none());
+        offset += block.len;
+    }
+    assert_eq!(offset, fs::metadata(path).unwrap().len() as usize);
+
+    assert_eq!(blocks[3].fields["identifier"], "NETSCAPE2.0");
+    assert_eq!(blocks[3].fields["repeat"], 5);
+    assert_eq!(blocks[4].fields["text"], "Created with GIMP");
+    assert_eq!(blocks[7].fields["frame"], 1);
+    assert_eq!(blocks[7].fields["delay_cs"], 3);
+    assert_eq!(blocks[8].fields["left"], 64);
+    assert_eq!(blocks[8].fields["lzw_min_code_size"], 8);
+}
+
+#[test]
+fn test_dump_hex_covers_raw_bytes() {
+    let path = Path::new("tests/testdata/timing/variable-delays.gif");
+    let blocks = dump_blocks(path, true).unwrap();
+
+    let hex: String = blocks.iter().map(|b| b.hex.clone().unwrap()).collect();
+    let expected: String = fs::read(path)
+        .unwrap()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    assert_eq!(hex, expected);
+
+    let delays: Vec<_> = blocks
+        .iter()
+        .filter(|b| b.kind == "graphic_control")
+        .map(|b| b.fields["delay_cs"].as_u64().unwrap())
+        .collect();
+    assert_eq!(delays, [20, 100, 50]);
+}
+
+#[test]
+fn test_dump_decodes_each_metadata_block_separately() {
+    let data = fs::read("tests/testdata/loop/2.gif").unwrap();
+    let first = xmp::xmp_block(b"<x:xmpmeta/>");
+    let second = xmp::xmp_block(b"<x:xmpmeta>longer packet</x:xmpmeta>");
+
+    let mut gif = data[..821].to_vec();
+    gif.extend_from_slice(&first);
+    gif.extend_from_slice(&icc::icc_block(&common::profile("First", 200)));
+    gif.extend_from_slice(&second);
+    gif.extend_from_slice(&icc::icc_block(&common::profile("Second", 200)));
+    gif.extend_from_slice(&data[821..]);
+
+    let blocks = dump_blocks_from_bytes(&gif, false).unwrap();
+    let fields: Vec<_> = blocks
+        .iter()
+        .filter(|b| b.kind == "application")
+        .map(|b| &b.fields)
+        .collect();
+    assert_eq!(fields[1]["xmp_len"], 12);
+    assert_eq!(fields[2]["description"], "First");
+    assert_eq!(fields[3]["xmp_len"], 36);
+    assert_eq!(fields[4]["description"], "Second");
+}
@@ -0,0 +1,67 @@
+use std::fs;
+use std::time::Duration;
+
+use gifmeta::gifmeta_structs::{GifError, ModifyOptions};
+use gifmeta::utils::parse_csv::parse_duration;
+
+/// variable-delays.gif has delays of 20, 100 and 50 cs (1.7 s).
+const INPUT: &str = "tests/testdata/timing/variable-delays.gif";
+
+#[test]
+fn test_duration_scales_delays_to_the_total() {
+    let data = fs::read(INPUT).unwrap();
+    let options = ModifyOptions {
+        duration: Some(parse_duration("2.4s").unwrap()),
+        ..Default::default()
+    };
+    let (modified, report) = gifmeta::mod_gif_from_bytes(&data, &options).unwrap();
+
+    let meta = gifmeta::get_metadata_from_bytes(&modified, true).unwrap();
+    let delays: Vec<u16> = meta.frames.iter().map(|f| f.delay_cs).collect();
+    assert_eq!(delays, [28, 141, 71]);
+    assert_eq!(meta.total_duration_cs, 240);
+
+    let timing = report.timing.unwrap();
+    assert_eq!(timing.old_duration_cs, 170);
+    assert_eq!(timing.new_duration_cs, 240);
+    assert!((timing.requested_duration_cs - 240.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_fps_sets_even_timing_and_reports_what_was_achieved() {
+    let data = fs::read(INPUT).unwrap();
+    let options = ModifyOptions {
+        fps: Some(30.0),
+        ..Default::default()
+    };
+    let plan = gifmeta::plan_mod_gif_from_bytes(&data, &options).unwrap();
+    let delays: Vec<u16> = plan.delays.iter().map(|c| c.new_cs).collect();
+    assert_eq!(delays, [3, 4, 3]);
+
+    let timing = plan.timing.unwrap();
+    assert_eq!(timing.new_duration_cs, 10);
+    assert!((timing.fps().unwrap() - 30.0).abs() < 1e-9);
+
+    // 0.333 s cannot be matched in whole centiseconds
+    let options = ModifyOptions {
+        duration: Some(Duration::from_millis(333)),
+        ..Default::default()
+    };
+    let timing = gifmeta::plan_mod_gif_from_bytes(&data, &options)
+        .unwrap()
+        .timing
+        .unwrap();
+    assert_eq!(timing.new_duration_cs, 33);
+    assert!((timing.requested_duration_cs - 33.3).abs() < 1e-9);
+
+    let options = ModifyOptions {
+        fps: Some(0.0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        gifmeta::mod_gif_from_bytes(&data, &options),
+        Err(GifError::InvalidSpec { .. })
+    ));
+    assert!(parse_duration("0ms").is_err());
+    assert_eq!(parse_duration("1500").unwrap(), Duration::from_secs(1500));
+}
@@ -19,3 +19,23 @@ fn test_extract_single_frame_as_png() {
     fs::create_dir_all(debug_path.parent().unwrap()).unwrap();
     fs::write(debug_path, png_bytes).expect("Failed to write debug image");
 }
+
+#[test]
+fn test_extract_frame_out_of_range_is_typed() {
+    match get_frame_image("tests/testdata/loop/2frame-soft.gif".into(), 5) {
+        Err(gifmeta::GifError::FrameOutOfRange { index, frame_count }) => {
+            assert_eq!(index, 5);
+            assert_eq!(frame_count, 2);
+        }
+        other => panic!("Expected FrameOutOfRange, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_missing_file_keeps_io_source() {
+    use std::error::Error;
+
+    let err = gifmeta::get_metadata(Path::new("tests/testdata/missing.gif"), false).unwrap_err();
+    assert!(matches!(err, gifmeta::GifError::Io { .. }));
+    assert!(err.source().is_some());
+}
@@ -0,0 +1,64 @@
+use std::fs;
+
+use gifmeta::gifmeta_structs::{FrameIndex, GifError, ModifyOptions};
+use gifmeta::utils::parse_csv::{parse_frame_delays, parse_frame_selector};
+
+#[test]
+fn test_selectors_resolve_against_frame_count() {
+    let frames = |spec: &str, count: usize| parse_frame_selector(spec).unwrap().frames(count);
+
+    assert_eq!(frames("2", 5), vec![2]);
+    assert_eq!(frames("-1", 5), vec![4]);
+    assert_eq!(frames("last", 3), vec![2]);
+    assert_eq!(frames("first", 3), vec![0]);
+    assert_eq!(frames("1-3", 5), vec![1, 2, 3]);
+    assert_eq!(frames("3-", 5), vec![3, 4]);
+    assert_eq!(frames("-3--1", 5), vec![2, 3, 4]);
+    assert_eq!(frames("0-20/2", 7), vec![0, 2, 4, 6]);
+    assert_eq!(frames("1-/3", 10), vec![1, 4, 7]);
+
+    // Frames the GIF does not have are skipped
+    assert_eq!(frames("7", 5), Vec::<usize>::new());
+    assert_eq!(frames("-9", 5), Vec::<usize>::new());
+    assert_eq!(frames("3-10", 5), vec![3, 4]);
+    assert_eq!(frames("-9-1", 5), vec![0, 1]);
+
+    let selector = parse_frame_selector("last").unwrap();
+    assert_eq!(selector.start, FrameIndex::FromEnd(1));
+    assert_eq!(selector.end, Some(FrameIndex::FromEnd(1)));
+}
+
+#[test]
+fn test_invalid_selectors_point_at_the_token() {
+    let error = |spec: &str| match parse_frame_delays(spec) {
+        Err(GifError::InvalidSpec { input, message }) => (input, message),
+        other => panic!("expected InvalidSpec for {spec:?}, got {other:?}"),
+    };
+
+    let (input, message) = error("0-9=5,2-x=4");
+    assert_eq!(input, "x");
+    assert!(message.contains("character 9"), "{message}");
+
+    assert_eq!(error("0-9=5, 4").0, "4");
+    assert_eq!(error("5-2=1").0, "5-2");
+    assert_eq!(error("-0=1").0, "-0");
+    assert_eq!(error("3/2=1").0, "3/2");
+    assert_eq!(error("0-9/0=1").0, "0");
+    assert_eq!(error("last=fast").0, "fast");
+    assert_eq!(error("lastx=1").0, "x");
+}
+
+#[test]
+fn test_mod_applies_selectors_in_order() {
+    let data = fs::read("tests/testdata/timing/variable-delays.gif").unwrap();
+    let options = ModifyOptions {
+        delay_all: Some(10),
+        frame_delays: parse_frame_delays("1-=40,last=200").unwrap(),
+        ..Default::default()
+    };
+
+    let (modified, _) = gifmeta::mod_gif_from_bytes(&data, &options).unwrap();
+    let meta = gifmeta::get_metadata_from_bytes(&modified, true).unwrap();
+    let delays: Vec<u16> = meta.frames.iter().map(|f| f.delay_cs).collect();
+    assert_eq!(delays, [10, 40, 200]);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use gifmeta::blocks;
+use gifmeta::gifmeta_structs::ModifyOptions;
+use gifmeta::utils::icc::ICC_IDENTIFIER;
+use gifmeta::{get_icc_profile, GifError};
+
+#[test]
+fn test_icc_embed_export_and_strip() {
+    let input = Path::new("tests/testdata/loop/2frame-soft.gif");
+    let with_icc = Path::new("tests/testdata/loop/2frame-soft-icc.gif");
+    let stripped = Path::new("tests/testdata/loop/2frame-soft-icc-stripped.gif");
+
+    // Large enough to span several sub-blocks
+    let icc = common::profile("sRGB IEC61966-2.1", 3144);
+    common::modify(
+        input,
+        with_icc,
+        ModifyOptions {
+            icc_profile: Some(icc.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let gif = blocks::read_file(with_icc).unwrap();
+    let app = gif
+        .applications()
+        .find(|a| a.identifier == ICC_IDENTIFIER)
+        .unwrap();
+    assert_eq!(app.data.chunks.len(), 3144usize.div_ceil(255));
+
+    assert_eq!(get_icc_profile(with_icc).unwrap(), Some(icc));
+    let meta = gifmeta::get_metadata(with_icc, false).unwrap();
+    let summary = meta.icc_profile.unwrap();
+    assert_eq!(summary.size, 3144);
+    assert_eq!(summary.description.as_deref(), Some("sRGB IEC61966-2.1"));
+    assert_eq!(meta.frame_count, 2);
+
+    common::modify(
+        with_icc,
+        stripped,
+        ModifyOptions {
+            strip_icc: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(fs::read(stripped).unwrap(), fs::read(input).unwrap());
+
+    fs::remove_file(with_icc).unwrap();
+    fs::remove_file(stripped).unwrap();
+}
+
+#[test]
+fn test_icc_rejects_non_profile() {
+    let input = Path::new("tests/testdata/loop/2frame-soft.gif");
+    let output = Path::new("tests/testdata/loop/2frame-soft-icc-bad.gif");
+
+    let result = common::modify(
+        input,
+        output,
+        ModifyOptions {
+            icc_profile: Some(b"not a color profile".to_vec()),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(result, Err(GifError::InvalidSpec { .. })));
+    assert!(!output.exists());
+    assert_eq!(get_icc_profile(input).unwrap(), None);
+}
+
+#[test]
+fn test_icc_oversized_tag_table() {
+    // A tag count far beyond the entries the profile actually holds
+    let mut icc = common::profile("sRGB", 300);
+    icc[128..132].copy_from_slice(&u32::MAX.to_be_bytes());
+    icc[132..136].copy_from_slice(b"wtpt");
+    let data = fs::read("tests/testdata/loop/2frame-soft.gif").unwrap();
+    let options = ModifyOptions {
+        icc_profile: Some(icc),
+        ..Default::default()
+    };
+    let (with_icc, _) = gifmeta::mod_gif_from_bytes(&data, &options).unwrap();
+
+    let summary = gifmeta::get_metadata_from_bytes(&with_icc, false)
+        .unwrap()
+        .icc_profile
+        .unwrap();
+    assert_eq!(summary.size, 300);
+    assert_eq!(summary.description, None);
+}
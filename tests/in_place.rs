@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use gifmeta::gifmeta_structs::{GifError, InPlaceOptions, ModifyOptions};
+
+/// Copies 2.gif into its own scratch directory, with an old modification time.
+fn scratch(dir: &str) -> PathBuf {
+    let dir = Path::new("tests/output").join(dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("2.gif");
+    fs::copy("tests/testdata/loop/2.gif", &path).unwrap();
+    let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+    path
+}
+
+/// Temp files left next to `path`.
+fn temp_files(path: &Path) -> Vec<String> {
+    fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with(".gifmeta-"))
+        .collect()
+}
+
+#[test]
+fn test_edit_in_place_keeps_backup_and_mtime() {
+    let path = &scratch("in-place-backup");
+    let original = fs::read(path).unwrap();
+    let mtime = fs::metadata(path).unwrap().modified().unwrap();
+
+    let options = ModifyOptions {
+        loop_count: Some(0),
+        ..Default::default()
+    };
+    let in_place = InPlaceOptions {
+        backup_suffix: Some(".orig".to_string()),
+        preserve_mtime: true,
+    };
+    gifmeta::edit_in_place(path, &in_place, |data| {
+        gifmeta::mod_gif_from_bytes(data, &options)
+    })
+    .unwrap();
+
+    let meta = gifmeta::get_metadata(path, false).unwrap();
+    assert_eq!(meta.repeat_count, Some(0));
+    assert_eq!(fs::metadata(path).unwrap().modified().unwrap(), mtime);
+
+    let backup = path.with_extension("gif.orig");
+    assert_eq!(fs::read(backup).unwrap(), original);
+    assert!(temp_files(path).is_empty());
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_failed_edit_leaves_file_untouched() {
+    let path = &scratch("in-place-failed");
+    let original = fs::read(path).unwrap();
+
+    let result = gifmeta::edit_in_place(path, &InPlaceOptions::default(), |data| {
+        gifmeta::mod_gif_from_bytes(data, &ModifyOptions::default())
+    });
+    assert!(matches!(result, Err(GifError::NoModifications)));
+    assert_eq!(fs::read(path).unwrap(), original);
+    assert!(temp_files(path).is_empty());
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_set_loop_count_replaces_input_keeping_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = &scratch("in-place-mode");
+    fs::set_permissions(path, fs::Permissions::from_mode(0o640)).unwrap();
+
+    gifmeta::set_loop_count(path, 4, None).unwrap();
+
+    let meta = gifmeta::get_metadata(path, false).unwrap();
+    assert_eq!(meta.repeat_count, Some(4));
+    let mode = fs::metadata(path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_mod_never_replaces_input_without_being_asked() {
+    let path = &scratch("in-place-mod");
+    let original = fs::read(path).unwrap();
+    let options = ModifyOptions {
+        delay_all: Some(9),
+        ..Default::default()
+    };
+
+    // Without an output the result goes to a new temp file
+    let report = gifmeta::mod_gif_with_options(path, None, &options).unwrap();
+    let temp = report.output.unwrap();
+    assert!(temp.starts_with(std::env::temp_dir()));
+    let meta = gifmeta::get_metadata(&temp, true).unwrap();
+    assert!(meta.frames.iter().all(|f| f.delay_cs == 9));
+    assert_eq!(fs::read(path).unwrap(), original);
+    fs::remove_file(temp).unwrap();
+
+    // An explicit output is written through a sibling temp file as well
+    let output = path.with_file_name("2-mod.gif");
+    gifmeta::mod_gif_with_options(path, Some(output.clone()), &options).unwrap();
+    assert_eq!(
+        gifmeta::get_metadata(&output, false).unwrap().frame_count,
+        2
+    );
+    assert!(temp_files(path).is_empty());
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
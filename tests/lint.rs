@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::Path;
+
+use gifmeta::gifmeta_structs::Severity;
+use gifmeta::lint_gif;
+
+fn netscape() -> Vec<u8> {
+    let mut block = vec![0x21, 0xFF, 0x0B];
+    block.extend_from_slice(b"NETSCAPE2.0");
+    block.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+    block
+}
+
+/// A small GIF87a file breaking as many rules as possible.
+fn broken_gif() -> Vec<u8> {
+    let mut data = b"GIF87a".to_vec();
+    data.extend_from_slice(&[2, 0, 2, 0, 0x80, 0, 0]); // 2×2 screen, 2-entry palette
+    data.extend_from_slice(&[0, 0, 0, 255, 255, 255]);
+    data.extend(netscape()); // 19
+    data.extend(netscape()); // 38
+                             // GCE with transparent index 5 (57)
+    data.extend_from_slice(&[0x21, 0xF9, 0x04, 0x01, 0x0A, 0x00, 0x05, 0x00]);
+    // 4×4 frame on a 2×2 screen (65)
+    data.extend_from_slice(&[
+        0x2C, 0, 0, 0, 0, 4, 0, 4, 0, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00,
+    ]);
+    // GCE that no image follows (80)
+    data.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00, 0x0A, 0x00, 0x00, 0x00]);
+    data.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00, 0x0A, 0x00, 0x00, 0x00]);
+    // 0×0 frame with LZW minimum code size 12 (96)
+    data.extend_from_slice(&[0x2C, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x0C, 0x01, 0x00, 0x00]);
+    // No trailer (110)
+    data
+}
+
+#[test]
+fn test_lint_clean_file() {
+    let report = lint_gif(Path::new("tests/testdata/loop/2.gif")).unwrap();
+    assert!(report.findings.is_empty(), "{:?}", report.findings);
+    assert!(!report.has_errors());
+}
+
+#[test]
+fn test_lint_reports_codes_severities_and_offsets() {
+    let path = Path::new("tests/testdata/loop/lint-broken.gif");
+    fs::write(path, broken_gif()).unwrap();
+    let report = lint_gif(path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    let found: Vec<_> = report
+        .findings
+        .iter()
+        .map(|f| (f.code, f.severity, f.offset))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("extensions-in-87a", Severity::Warning, 19),
+            ("duplicate-loop-extension", Severity::Warning, 38),
+            ("frame-outside-screen", Severity::Warning, 65),
+            ("transparent-index-out-of-range", Severity::Warning, 65),
+            ("gce-without-image", Severity::Warning, 80),
+            ("zero-size-frame", Severity::Error, 96),
+            ("lzw-code-size", Severity::Error, 106),
+            ("missing-trailer", Severity::Error, 110),
+        ]
+    );
+    assert_eq!((report.errors, report.warnings), (3, 5));
+    assert!(report.has_errors());
+}
+
+#[test]
+fn test_lint_truncated_file_is_a_finding() {
+    let path = Path::new("tests/testdata/loop/lint-truncated.gif");
+    let data = fs::read("tests/testdata/loop/2.gif").unwrap();
+    fs::write(path, &data[..1000]).unwrap();
+    let report = lint_gif(path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(report.findings[0].code, "parse-error");
+    // The sub-block cut short by the truncation
+    assert_eq!(report.findings[0].offset, 841);
+    assert!(report.findings[0]
+        .message
+        .contains("Unexpected end of file"));
+    assert!(report.has_errors());
+}
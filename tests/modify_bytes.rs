@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use gifmeta::blocks;
+use gifmeta::mod_gif;
+
+/// Returns the offsets at which two equally sized buffers differ.
+fn diff_offsets(a: &[u8], b: &[u8]) -> Vec<usize> {
+    assert_eq!(a.len(), b.len(), "File size changed");
+    (0..a.len()).filter(|&i| a[i] != b[i]).collect()
+}
+
+#[test]
+fn test_mod_delays_only_touches_gce_delay_fields() {
+    let input = Path::new("tests/testdata/timing/variable-delays.gif");
+    let output = Path::new("tests/testdata/timing/variable-delays-bytes.gif");
+
+    let mut delays = HashMap::new();
+    delays.insert(2, 7);
+    let result = mod_gif(
+        &input.to_path_buf(),
+        Some(output.to_path_buf()),
+        None,
+        Some(33),
+        Some(delays),
+    );
+    assert!(result.is_ok());
+
+    let before = fs::read(input).unwrap();
+    let after = fs::read(output).unwrap();
+
+    let gif = blocks::parse(before.clone()).unwrap();
+    let delay_fields: Vec<usize> = gif
+        .frames()
+        .iter()
+        .flat_map(|(gce, _)| {
+            let offset = gce.unwrap().delay_offset;
+            [offset, offset + 1]
+        })
+        .collect();
+    for offset in diff_offsets(&before, &after) {
+        assert!(
+            delay_fields.contains(&offset),
+            "Unexpected change at offset {}",
+            offset
+        );
+    }
+
+    let patched = blocks::parse(after).unwrap();
+    let new_delays: Vec<u16> = patched
+        .frames()
+        .iter()
+        .map(|(gce, _)| gce.unwrap().delay_cs)
+        .collect();
+    assert_eq!(new_delays, vec![33, 33, 7]);
+
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_mod_loop_count_only_touches_netscape_field() {
+    let input = Path::new("tests/testdata/loop/10frame-rainbow.gif");
+    let output = Path::new("tests/testdata/loop/10frame-rainbow-bytes.gif");
+
+    let result = mod_gif(
+        &input.to_path_buf(),
+        Some(output.to_path_buf()),
+        Some(4),
+        None,
+        None,
+    );
+    assert!(result.is_ok());
+
+    let before = fs::read(input).unwrap();
+    let after = fs::read(output).unwrap();
+    let netscape = before
+        .windows(11)
+        .position(|w| w == b"NETSCAPE2.0")
+        .unwrap();
+
+    assert_eq!(
+        diff_offsets(&before, &after),
+        vec![netscape + 13],
+        "Only the low byte of the repeat count should change"
+    );
+
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_mod_inserts_gce_for_frames_without_one() {
+    let input = Path::new("tests/testdata/loop/1.gif");
+    let output = Path::new("tests/testdata/loop/1-bytes.gif");
+
+    let result = mod_gif(
+        &input.to_path_buf(),
+        Some(output.to_path_buf()),
+        None,
+        Some(12),
+        None,
+    );
+    assert!(result.is_ok());
+
+    let before = blocks::read_file(input).unwrap();
+    let after = blocks::read_file(output).unwrap();
+    assert_eq!(after.frames().len(), before.frames().len());
+    for ((gce, img), (_, orig)) in after.frames().iter().zip(before.frames()) {
+        assert_eq!(gce.unwrap().delay_cs, 12);
+        assert_eq!(after.bytes(img.span), before.bytes(orig.span));
+    }
+
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_mod_preserves_comments_and_reports_them() {
+    let input = Path::new("tests/testdata/loop/2.gif");
+    let output = Path::new("tests/testdata/loop/2-preserve.gif");
+
+    let report = mod_gif(
+        &input.to_path_buf(),
+        Some(output.to_path_buf()),
+        Some(0),
+        Some(8),
+        None,
+    )
+    .unwrap();
+
+    assert!(report.dropped.is_empty(), "Dropped: {:?}", report.dropped);
+    let kinds: Vec<&str> = report.preserved.iter().map(|b| b.kind.as_str()).collect();
+    assert_eq!(kinds, vec!["application", "comment"]);
+
+    let before = blocks::read_file(input).unwrap();
+    let after = blocks::read_file(output).unwrap();
+    let comment = |gif: &blocks::GifFile| {
+        gif.blocks.iter().find_map(|b| match b {
+            blocks::Block::Comment(c) => Some(c.data.collect(&gif.data)),
+            _ => None,
+        })
+    };
+    assert_eq!(comment(&after), comment(&before));
+    assert_eq!(comment(&after).unwrap(), b"Created with GIMP");
+
+    fs::remove_file(output).unwrap();
+}
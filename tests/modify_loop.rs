@@ -1,3 +1,6 @@
+// These tests predate get_metadata taking &Path and still pass &PathBuf
+#![allow(clippy::unnecessary_to_owned)]
+
 use std::fs;
 use std::path::Path;
 // use gifmeta::parse_csv; // Removed because there is no parse_csv in the root
@@ -117,3 +120,206 @@ fn test_mod_preserves_loop_count_if_not_specified() {
         "Loop count should remain unchanged"
     );
 }
+
+#[test]
+fn test_loop_reader_follows_block_structure() {
+    use gifmeta::blocks;
+    use gifmeta::gifmeta_structs::LoopExtension;
+    use gifmeta::utils::loop_count::read_loop_extension;
+
+    // A comment whose text looks exactly like a loop extension must not be picked up
+    let mut data = fs::read("tests/testdata/loop/loop-once.gif").unwrap();
+    let mut fake = vec![0x21, 0xFE, 0x13, 0x21, 0xFF, 0x0B];
+    fake.extend_from_slice(b"NETSCAPE2.0");
+    fake.extend_from_slice(&[0x03, 0x01, 0x05, 0x00, 0x00, 0x00]);
+    data.splice(19..19, fake);
+    let gif = blocks::parse(data).unwrap();
+    assert_eq!(read_loop_extension(&gif), LoopExtension::Missing);
+
+    // A loop sub-block with the wrong size is reported as malformed
+    let mut data = fs::read("tests/testdata/loop/no-loop-flag.gif").unwrap();
+    data.splice(0x21..0x26, [0x02, 0x01, 0x00, 0x00]);
+    let gif = blocks::parse(data).unwrap();
+    assert_eq!(
+        read_loop_extension(&gif),
+        LoopExtension::Malformed { offset: 0x13 }
+    );
+
+    let gif = blocks::read_file("tests/testdata/loop/2.gif").unwrap();
+    assert_eq!(
+        read_loop_extension(&gif),
+        LoopExtension::Count {
+            offset: 781,
+            repeat: 5
+        }
+    );
+}
+
+#[test]
+fn test_loop_reader_does_not_panic_on_truncated_file() {
+    use gifmeta::utils::loop_count::extract_loop_count;
+
+    let output_path = Path::new("tests/testdata/loop/truncated-0x21.gif");
+    let mut data = fs::read("tests/testdata/loop/loop-once.gif").unwrap();
+    data.truncate(19);
+    data.push(0x21);
+    fs::write(output_path, &data).unwrap();
+
+    let result = extract_loop_count(output_path);
+    assert!(matches!(result, Err(gifmeta::GifError::Decode { .. })));
+
+    fs::remove_file(output_path).unwrap();
+}
+
+#[test]
+fn test_metadata_distinguishes_missing_and_infinite_loop() {
+    use gifmeta::gifmeta_structs::PlayCount;
+
+    let once =
+        gifmeta::get_metadata(Path::new("tests/testdata/loop/loop-once.gif"), false).unwrap();
+    assert!(!once.has_loop_extension);
+    assert_eq!(once.repeat_count, None);
+    assert_eq!(once.total_plays, Some(PlayCount::Times(1)));
+    assert_eq!(once.loop_count, 1);
+
+    let forever =
+        gifmeta::get_metadata(Path::new("tests/testdata/loop/no-loop-flag.gif"), false).unwrap();
+    assert!(forever.has_loop_extension);
+    assert_eq!(forever.repeat_count, Some(0));
+    assert_eq!(forever.total_plays, Some(PlayCount::Infinite));
+    assert_eq!(forever.loop_count, 0);
+
+    let six = gifmeta::get_metadata(Path::new("tests/testdata/loop/2.gif"), false).unwrap();
+    assert_eq!(six.repeat_count, Some(5));
+    assert_eq!(six.total_plays, Some(PlayCount::Times(6)));
+
+    let json = serde_json::to_value(&forever).unwrap();
+    assert_eq!(json["total_plays"], "infinite");
+    assert_eq!(json["repeat_count"], 0);
+    let json = serde_json::to_value(&once).unwrap();
+    assert_eq!(json["total_plays"]["times"], 1);
+    assert_eq!(json["has_loop_extension"], false);
+}
+
+#[test]
+fn test_no_loop_removes_extension_and_round_trips() {
+    use gifmeta::gifmeta_structs::{ModifyOptions, PlayCount};
+
+    let input_path = Path::new("tests/testdata/loop/no-loop-flag.gif");
+    let removed_path = Path::new("tests/testdata/loop/no-loop-flag-removed.gif");
+    let restored_path = Path::new("tests/testdata/loop/no-loop-flag-restored.gif");
+
+    let options = ModifyOptions {
+        remove_loop: true,
+        ..Default::default()
+    };
+    let result = gifmeta::mod_gif_with_options(
+        &input_path.to_path_buf(),
+        Some(removed_path.to_path_buf()),
+        &options,
+    );
+    assert!(result.unwrap().dropped.is_empty());
+
+    let removed = gifmeta::get_metadata(removed_path, false).unwrap();
+    assert!(!removed.has_loop_extension);
+    assert_eq!(removed.total_plays, Some(PlayCount::Times(1)));
+    assert_eq!(
+        fs::read(input_path).unwrap().len() - fs::read(removed_path).unwrap().len(),
+        19,
+        "Only the 19-byte NETSCAPE block should be removed"
+    );
+
+    // Putting an infinite loop back yields the original file byte for byte
+    let result = mod_gif(
+        &removed_path.to_path_buf(),
+        Some(restored_path.to_path_buf()),
+        Some(0),
+        None,
+        None,
+    );
+    assert!(result.is_ok());
+    assert_eq!(
+        fs::read(restored_path).unwrap(),
+        fs::read(input_path).unwrap()
+    );
+
+    fs::remove_file(removed_path).unwrap();
+    fs::remove_file(restored_path).unwrap();
+}
+
+#[test]
+fn test_animexts_is_read_and_normalized() {
+    use gifmeta::gifmeta_structs::{ModifyOptions, PlayCount};
+
+    let input_path = Path::new("tests/testdata/loop/animexts.gif");
+    let output_path = Path::new("tests/testdata/loop/animexts-normalized.gif");
+    let original = fs::read("tests/testdata/loop/2.gif").unwrap();
+    let mut animexts = original.clone();
+    animexts[784..795].copy_from_slice(b"ANIMEXTS1.0");
+    fs::write(input_path, &animexts).unwrap();
+
+    let meta = gifmeta::get_metadata(input_path, false).unwrap();
+    assert_eq!(meta.loop_identifier.as_deref(), Some("ANIMEXTS1.0"));
+    assert_eq!(meta.repeat_count, Some(5));
+    assert_eq!(meta.total_plays, Some(PlayCount::Times(6)));
+
+    let options = ModifyOptions {
+        normalize_loop: true,
+        ..Default::default()
+    };
+    let result = gifmeta::mod_gif_with_options(
+        &input_path.to_path_buf(),
+        Some(output_path.to_path_buf()),
+        &options,
+    );
+    assert!(result.unwrap().dropped.is_empty());
+    assert_eq!(fs::read(output_path).unwrap(), original);
+
+    fs::remove_file(input_path).unwrap();
+    fs::remove_file(output_path).unwrap();
+}
+
+#[test]
+fn test_loop_buffer_sub_block_is_written_and_reported() {
+    use gifmeta::gifmeta_structs::ModifyOptions;
+
+    let input_path = Path::new("tests/testdata/loop/2frame-soft.gif");
+    let output_path = Path::new("tests/testdata/loop/2frame-soft-buffer.gif");
+
+    let options = ModifyOptions {
+        loop_count: Some(3),
+        loop_buffer_size: Some(65536),
+        ..Default::default()
+    };
+    let result = gifmeta::mod_gif_with_options(
+        &input_path.to_path_buf(),
+        Some(output_path.to_path_buf()),
+        &options,
+    );
+    assert!(result.is_ok());
+
+    let meta = gifmeta::get_metadata(output_path, false).unwrap();
+    assert_eq!(meta.loop_buffer_size, Some(65536));
+    assert_eq!(meta.repeat_count, Some(2));
+    assert_eq!(
+        fs::read(output_path).unwrap().len(),
+        fs::read(input_path).unwrap().len() + 6
+    );
+
+    // Buffering alone cannot create a loop extension
+    let options = ModifyOptions {
+        loop_buffer_size: Some(1024),
+        ..Default::default()
+    };
+    let result = gifmeta::mod_gif_with_options(
+        &"tests/testdata/loop/loop-once.gif".into(),
+        Some(output_path.to_path_buf()),
+        &options,
+    );
+    assert!(matches!(
+        result,
+        Err(gifmeta::GifError::MissingLoopExtension)
+    ));
+
+    fs::remove_file(output_path).unwrap();
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::Path;
+
+use gifmeta::blocks;
+use gifmeta::gifmeta_structs::{ModifyOptions, PreviewOptions, StripOptions};
+use gifmeta::utils::parse_csv::parse_frame_delays;
+use gifmeta::{
+    get_frame_image, get_frame_image_with_options, get_metadata, get_metadata_from_bytes,
+    mod_gif_from_bytes, strip_gif_from_bytes,
+};
+
+/// Writes a copy of 2.gif with an 8×8-cell Plain Text Extension after the last frame.
+fn with_plain_text(output: &Path, left: u16, top: u16, foreground: u8, background: u8) {
+    let mut data = fs::read("tests/testdata/loop/2.gif").unwrap();
+    let trailer = blocks::parse(data.clone()).unwrap().trailer.unwrap();
+
+    let mut block = vec![0x21, 0xF9, 0x04, 0x00, 0x32, 0x00, 0x00, 0x00];
+    block.extend_from_slice(&[0x21, 0x01, 0x0C]);
+    for value in [left, top, 16, 8] {
+        block.extend_from_slice(&value.to_le_bytes());
+    }
+    block.extend_from_slice(&[8, 8, foreground, background]);
+    block.extend_from_slice(&[0x03, b'H', b'i', b'!', 0x00]);
+    data.splice(trailer..trailer, block);
+
+    fs::write(output, data).unwrap();
+}
+
+fn palette_rgb(gif: &blocks::GifFile, index: u8) -> [u8; 3] {
+    let start = gif.global_palette.as_ref().unwrap().span.offset + index as usize * 3;
+    [gif.data[start], gif.data[start + 1], gif.data[start + 2]]
+}
+
+#[test]
+fn test_plain_text_is_reported_with_its_frame() {
+    let path = Path::new("tests/testdata/loop/2-plain-text-info.gif");
+    with_plain_text(path, 10, 20, 1, 0);
+
+    let meta = get_metadata(path, true).unwrap();
+    assert_eq!(meta.frame_count, 2);
+    assert!(meta.frames[0].plain_text.is_empty());
+
+    let texts = &meta.frames[1].plain_text;
+    assert_eq!(texts.len(), 1);
+    let text = &texts[0];
+    assert_eq!(text.offset, 14884 + 8);
+    assert_eq!(
+        (text.left, text.top, text.width, text.height),
+        (10, 20, 16, 8)
+    );
+    assert_eq!((text.cell_width, text.cell_height), (8, 8));
+    assert_eq!((text.foreground_index, text.background_index), (1, 0));
+    assert_eq!(text.delay_cs, Some(50));
+    // Only two cells fit the grid, but the full text is reported
+    assert_eq!(text.text, "Hi!");
+    assert_eq!(meta.plain_text, *texts);
+
+    // Listed at the top level even without frames
+    let meta = get_metadata(path, false).unwrap();
+    assert!(meta.frames.is_empty());
+    assert_eq!(meta.plain_text.len(), 1);
+    assert_eq!(meta.plain_text[0].frame, 1);
+    assert_eq!(meta.plain_text[0].text, "Hi!");
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_plain_text_rendering_is_opt_in() {
+    let path = Path::new("tests/testdata/loop/2-plain-text-render.gif");
+    with_plain_text(path, 0, 0, 1, 0);
+    let gif = blocks::read_file(path).unwrap();
+    let (fg, bg) = (palette_rgb(&gif, 1), palette_rgb(&gif, 0));
+    assert_ne!(fg, bg);
+
+    let decode = |png: Vec<u8>| image::load_from_memory(&png).unwrap().to_rgba8();
+    let plain = decode(get_frame_image(path.to_string_lossy().into(), 1).unwrap());
+    let options = PreviewOptions {
+        render_plain_text: true,
+    };
+    let rendered =
+        decode(get_frame_image_with_options(path.to_string_lossy().into(), 1, &options).unwrap());
+
+    // 'H' starts with two lit pixels, then a gap
+    assert_eq!(rendered.get_pixel(0, 0).0[..3], fg);
+    assert_eq!(rendered.get_pixel(1, 0).0[..3], fg);
+    assert_eq!(rendered.get_pixel(2, 0).0[..3], bg);
+    // Nothing is drawn outside the 16×8 grid
+    assert_eq!(rendered.get_pixel(16, 0), plain.get_pixel(16, 0));
+    assert_eq!(rendered.get_pixel(0, 8), plain.get_pixel(0, 8));
+    // Frames before the text are unaffected
+    let first = get_frame_image_with_options(path.to_string_lossy().into(), 0, &options).unwrap();
+    assert_eq!(
+        first,
+        get_frame_image(path.to_string_lossy().into(), 0).unwrap()
+    );
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_graphic_control_applies_to_image_after_plain_text() {
+    // GCE → Plain Text Extension → image, as in frame 1 of this fixture
+    let data = fs::read("tests/testdata/loop/2-gce-plain-text.gif").unwrap();
+    let gif = blocks::parse(data.clone()).unwrap();
+    let (control, _) = gif.frames()[1];
+    assert_eq!(control.map(|gce| gce.delay_cs), Some(3));
+
+    let options = ModifyOptions {
+        frame_delays: parse_frame_delays("1=+10").unwrap(),
+        verify: true,
+        ..Default::default()
+    };
+    let (modified, _) = mod_gif_from_bytes(&data, &options).unwrap();
+    // The delay is patched in place; no second GCE is inserted
+    assert_eq!(modified.len(), data.len());
+    let meta = get_metadata_from_bytes(&modified, true).unwrap();
+    assert_eq!(meta.frames[1].delay_cs, 13);
+    assert_eq!(meta.frames[1].transparent_index, Some(255));
+
+    // Stripping the text keeps the GCE the image relies on
+    let (stripped, _) = strip_gif_from_bytes(&data, &StripOptions::default()).unwrap();
+    let meta = get_metadata_from_bytes(&stripped, true).unwrap();
+    assert_eq!(meta.frames[1].delay_cs, 3);
+    assert_eq!(meta.frames[1].transparent_index, Some(255));
+}
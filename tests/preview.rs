@@ -1,3 +1,6 @@
+// These tests predate get_metadata taking &Path and still pass &PathBuf
+#![allow(clippy::unnecessary_to_owned)]
+
 #[cfg(test)]
 mod tests {
     use gifmeta::utils::extract_frame_as_png::extract_frame_as_png;
@@ -0,0 +1,109 @@
+use std::fs;
+use std::path::Path;
+
+use gifmeta::{
+    get_metadata, get_metadata_tolerant, get_metadata_tolerant_from_bytes, lint_gif, repair_gif,
+    repair_gif_from_bytes, GifError,
+};
+
+const ORIGINAL: &str = "tests/testdata/loop/2.gif";
+
+#[test]
+fn test_repair_salvages_frame_cut_mid_stream() {
+    let input = Path::new("tests/testdata/loop/2-cut-mid-frame.gif");
+    let output = Path::new("tests/testdata/loop/2-cut-mid-frame-repaired.gif");
+    let original = fs::read(ORIGINAL).unwrap();
+    fs::write(input, &original[..9000]).unwrap();
+    assert!(get_metadata(input, false).is_err());
+
+    let report = repair_gif(input, Some(output.into())).unwrap();
+    assert_eq!(report.frames, 2);
+    assert!(report
+        .fixes
+        .iter()
+        .any(|f| f.offset == 7758 && f.message.contains("cut after 50 of 166 rows")));
+
+    // Everything before the damaged frame is copied verbatim
+    let repaired = fs::read(output).unwrap();
+    assert_eq!(repaired[..7758], original[..7758]);
+    assert_eq!(repaired.last(), Some(&0x3B));
+
+    let meta = get_metadata(output, true).unwrap();
+    assert_eq!(meta.frame_count, 2);
+    assert_eq!(meta.repeat_count, Some(5));
+    assert!(!lint_gif(output).unwrap().has_errors());
+    gifmeta::get_frame_image(output.to_string_lossy().into(), 1).unwrap();
+
+    fs::remove_file(input).unwrap();
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_repair_appends_trailer_and_fixes_header() {
+    let input = Path::new("tests/testdata/loop/2-no-trailer.gif");
+    let output = Path::new("tests/testdata/loop/2-no-trailer-repaired.gif");
+    let original = fs::read(ORIGINAL).unwrap();
+    let mut damaged = original[..original.len() - 1].to_vec();
+    damaged[6..10].copy_from_slice(&[0, 0, 0, 0]); // 0×0 logical screen
+    fs::write(input, &damaged).unwrap();
+
+    let report = repair_gif(input, Some(output.into())).unwrap();
+    let messages: Vec<_> = report.fixes.iter().map(|f| f.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Enlarged the logical screen from 0×0 to 640×360 to fit every frame",
+            "Appended the missing trailer",
+        ]
+    );
+    assert_eq!(fs::read(output).unwrap(), original);
+
+    // Without an output the damaged file is left alone
+    let report = repair_gif(input, None).unwrap();
+    let temp = report.output.unwrap();
+    assert!(temp.starts_with(std::env::temp_dir()));
+    assert_eq!(fs::read(&temp).unwrap(), original);
+    assert_eq!(fs::read(input).unwrap(), damaged);
+    fs::remove_file(temp).unwrap();
+
+    fs::remove_file(input).unwrap();
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_tolerant_info_reports_partial_metadata() {
+    let input = Path::new("tests/testdata/loop/2-cut-tolerant.gif");
+    let original = fs::read(ORIGINAL).unwrap();
+    fs::write(input, &original[..3000]).unwrap();
+
+    let meta = get_metadata_tolerant(input, true).unwrap();
+    assert_eq!(meta.frame_count, 1);
+    assert_eq!(meta.comments[0].text, "Created with GIMP");
+    assert!(meta.warnings[0].starts_with("File is damaged"));
+    assert!(meta.warnings.iter().any(|w| w.contains("162 of 360 rows")));
+
+    fs::remove_file(input).unwrap();
+}
+
+#[test]
+fn test_repair_fails_when_cut_before_first_image() {
+    let cases = [
+        (ORIGINAL, 781..=832),
+        ("tests/testdata/loop/2frame-soft.gif", 19..=45),
+    ];
+    for (path, lengths) in cases {
+        let original = fs::read(path).unwrap();
+        for len in lengths {
+            let data = &original[..len];
+            let result = repair_gif_from_bytes(data);
+            assert!(
+                matches!(&result, Err(GifError::Decode { message, .. }) if message == "no frame could be salvaged"),
+                "{} cut to {} bytes: {:?}",
+                path,
+                len,
+                result.map(|(_, report)| report)
+            );
+            assert!(get_metadata_tolerant_from_bytes(data, false).is_err());
+        }
+    }
+}
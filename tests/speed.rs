@@ -0,0 +1,53 @@
+use std::fs;
+
+use gifmeta::gifmeta_structs::{GifError, ModifyOptions};
+
+/// variable-delays.gif has delays of 20, 100 and 50 cs.
+const INPUT: &str = "tests/testdata/timing/variable-delays.gif";
+
+fn delays_after(options: &ModifyOptions) -> Result<Vec<u16>, GifError> {
+    let data = fs::read(INPUT).unwrap();
+    let (modified, _) = gifmeta::mod_gif_from_bytes(&data, options)?;
+    let meta = gifmeta::get_metadata_from_bytes(&modified, true)?;
+    Ok(meta.frames.iter().map(|f| f.delay_cs).collect())
+}
+
+#[test]
+fn test_speed_carries_rounding_error() {
+    let options = ModifyOptions {
+        speed: Some(1.5),
+        ..Default::default()
+    };
+    // 13.33 + 66.67 + 33.33 = 113.33: each frame ends on the rounded running total
+    let delays = delays_after(&options).unwrap();
+    assert_eq!(delays, [13, 67, 33]);
+    assert_eq!(delays.iter().sum::<u16>(), 113);
+
+    let options = ModifyOptions {
+        speed: Some(0.5),
+        ..Default::default()
+    };
+    assert_eq!(delays_after(&options).unwrap(), [40, 200, 100]);
+}
+
+#[test]
+fn test_speed_applies_after_other_delay_changes() {
+    let options = ModifyOptions {
+        delay_all: Some(9),
+        speed: Some(2.0),
+        ..Default::default()
+    };
+    // 4.5, 9, 13.5 → 5, 9, 14
+    assert_eq!(delays_after(&options).unwrap(), [5, 4, 5]);
+
+    for speed in [0.0, -1.0, f64::NAN] {
+        let options = ModifyOptions {
+            speed: Some(speed),
+            ..Default::default()
+        };
+        assert!(matches!(
+            delays_after(&options),
+            Err(GifError::InvalidSpec { .. })
+        ));
+    }
+}
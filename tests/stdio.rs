@@ -0,0 +1,103 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use gifmeta::gifmeta_structs::{GifError, ModifyOptions, PreviewOptions};
+
+const INPUT: &str = "tests/testdata/loop/2.gif";
+
+#[test]
+fn test_byte_variants_match_file_variants() {
+    let data = fs::read(INPUT).unwrap();
+
+    let from_file = gifmeta::get_metadata(Path::new(INPUT), true).unwrap();
+    let from_bytes = gifmeta::get_metadata_from_bytes(&data, true).unwrap();
+    assert_eq!(
+        serde_json::to_value(&from_file).unwrap(),
+        serde_json::to_value(&from_bytes).unwrap()
+    );
+
+    let png_file = gifmeta::get_frame_image(INPUT.into(), 1).unwrap();
+    let png_bytes = gifmeta::get_frame_image_from_bytes(&data, 1, &PreviewOptions::default());
+    assert_eq!(png_file, png_bytes.unwrap());
+}
+
+#[test]
+fn test_mod_from_bytes_returns_modified_gif() {
+    let data = fs::read(INPUT).unwrap();
+    let options = ModifyOptions {
+        loop_count: Some(0),
+        delay_all: Some(7),
+        ..Default::default()
+    };
+
+    let (modified, report) = gifmeta::mod_gif_from_bytes(&data, &options).unwrap();
+    assert!(report.output.is_none());
+    assert!(report.dropped.is_empty());
+
+    let meta = gifmeta::get_metadata_from_bytes(&modified, true).unwrap();
+    assert_eq!(meta.repeat_count, Some(0));
+    assert!(meta.frames.iter().all(|f| f.delay_cs == 7));
+
+    let unchanged = gifmeta::mod_gif_from_bytes(&data, &ModifyOptions::default());
+    assert!(matches!(unchanged, Err(GifError::NoModifications)));
+}
+
+#[test]
+fn test_cli_reads_stdin_and_writes_stdout() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gifmeta"))
+        .args(["mod", "-", "--loop-count", "3"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let data = fs::read(INPUT).unwrap();
+    child.stdin.take().unwrap().write_all(&data).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    // Status lines go to stderr so stdout is nothing but the GIF
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("<stdout>"));
+    let meta = gifmeta::get_metadata_from_bytes(&output.stdout, false).unwrap();
+    assert_eq!(meta.repeat_count, Some(2));
+    assert_eq!(output.stdout.len(), data.len());
+}
+
+#[test]
+fn test_cli_mod_failures_exit_non_zero() {
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_gifmeta"))
+            .args(args)
+            .output()
+            .unwrap()
+            .status
+            .code()
+    };
+
+    let missing = ["mod", "tests/testdata/missing.gif", "--loop-count", "2"];
+    assert_eq!(
+        run(&[&missing[..], &["-o", "tests/output/x.gif"]].concat()),
+        Some(1)
+    );
+    assert_eq!(run(&["mod", INPUT, "-o", "tests/output/x.gif"]), Some(2));
+    assert_eq!(run(&["mod", INPUT, "--dry-run"]), Some(2));
+}
+
+#[test]
+fn test_cli_unwritable_output_exits_non_zero() {
+    let output = Command::new(env!("CARGO_BIN_EXE_gifmeta"))
+        .args([
+            "preview",
+            INPUT,
+            "--output",
+            "tests/output/missing/dir/frame.png",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("I/O error") && !stderr.contains("panicked"));
+}
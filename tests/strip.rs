@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::Path;
+
+use gifmeta::blocks;
+use gifmeta::gifmeta_structs::{StripCategory, StripOptions, StripReport};
+use gifmeta::strip_gif;
+use gifmeta::utils::{icc, patch, xmp};
+
+const XMP: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?><x:xmpmeta xmlns:x="adobe:ns:meta/"/><?xpacket end="w"?>"#;
+
+/// 2.gif with one block of every strippable kind added, plus trailing bytes.
+fn cluttered(output: &Path) -> Vec<u8> {
+    let original = fs::read("tests/testdata/loop/2.gif").unwrap();
+    let first_gce = 821;
+    let trailer = original.len() - 1;
+
+    let mut metadata = xmp::xmp_block(XMP.as_bytes());
+    metadata.extend(icc::icc_block(&[0xAB; 600]));
+    metadata.extend([0x21, 0xFF, 0x0B]);
+    metadata.extend(b"MYAPP1.0abc");
+    metadata.extend(patch::sub_blocks(b"private"));
+    metadata.extend([0x21, 0x99]);
+    metadata.extend(patch::sub_blocks(b"unknown"));
+
+    let mut text = vec![0x21, 0xF9, 0x04, 0x00, 0x0A, 0x00, 0x00, 0x00];
+    text.extend([0x21, 0x01, 0x0C, 0, 0, 0, 0, 16, 0, 8, 0, 8, 8, 1, 0]);
+    text.extend(patch::sub_blocks(b"Hi"));
+
+    let mut data = original[..first_gce].to_vec();
+    data.extend(metadata);
+    data.extend_from_slice(&original[first_gce..trailer]);
+    data.extend(text);
+    data.push(0x3B);
+    data.extend(b"junk after trailer");
+    fs::write(output, &data).unwrap();
+    data
+}
+
+fn removed(report: &StripReport, category: StripCategory) -> (usize, usize) {
+    report
+        .removed
+        .iter()
+        .find(|e| e.category == category)
+        .map(|e| (e.blocks, e.bytes))
+        .unwrap()
+}
+
+#[test]
+fn test_strip_keeps_only_what_renders_and_loops() {
+    let input = Path::new("tests/testdata/loop/2-cluttered.gif");
+    let output = Path::new("tests/testdata/loop/2-cluttered-stripped.gif");
+    let data = cluttered(input);
+
+    let report = strip_gif(input, Some(output.into()), &StripOptions::default()).unwrap();
+    assert_eq!(report.original_size, data.len());
+    assert!(report
+        .removed
+        .iter()
+        .all(|e| e.category != StripCategory::Loop));
+    assert_eq!(removed(&report, StripCategory::Comments), (1, 21));
+    assert_eq!(
+        removed(&report, StripCategory::Xmp),
+        (1, xmp::xmp_block(XMP.as_bytes()).len())
+    );
+    assert_eq!(removed(&report, StripCategory::Icc).0, 1);
+    assert_eq!(removed(&report, StripCategory::Applications), (1, 23));
+    assert_eq!(removed(&report, StripCategory::Extensions), (1, 11));
+    // The plain text's Graphic Control Extension goes with it
+    assert_eq!(removed(&report, StripCategory::PlainText), (1, 8 + 15 + 4));
+    assert_eq!(
+        removed(&report, StripCategory::Trailing),
+        (1, "junk after trailer".len())
+    );
+    let total: usize = report.removed.iter().map(|e| e.bytes).sum();
+    assert_eq!(report.original_size - report.stripped_size, total);
+
+    // What is left is 2.gif without its comment, byte for byte
+    let mut expected = fs::read("tests/testdata/loop/2.gif").unwrap();
+    expected.drain(800..821);
+    assert_eq!(fs::read(output).unwrap(), expected);
+
+    fs::remove_file(input).unwrap();
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_strip_keep_list() {
+    let input = Path::new("tests/testdata/loop/2-cluttered-keep.gif");
+    let output = Path::new("tests/testdata/loop/2-cluttered-keep-stripped.gif");
+    cluttered(input);
+
+    let options = StripOptions {
+        keep: vec![StripCategory::Icc, StripCategory::Xmp],
+    };
+    let report = strip_gif(input, Some(output.into()), &options).unwrap();
+    assert_eq!(removed(&report, StripCategory::Loop), (1, 19));
+
+    let gif = blocks::read_file(output).unwrap();
+    let ids: Vec<_> = gif.applications().map(|a| a.identifier.as_str()).collect();
+    assert_eq!(ids, [xmp::XMP_IDENTIFIER, icc::ICC_IDENTIFIER]);
+    assert_eq!(gif.frames().len(), 2);
+    assert_eq!(gif.trailing, None);
+
+    fs::remove_file(input).unwrap();
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_strip_without_output_leaves_input() {
+    let input = Path::new("tests/testdata/loop/2-cluttered-no-output.gif");
+    cluttered(input);
+    let original = fs::read(input).unwrap();
+
+    let report = strip_gif(input, None, &StripOptions::default()).unwrap();
+    let output = report.output.unwrap();
+    assert!(output.starts_with(std::env::temp_dir()));
+    assert_eq!(fs::read(input).unwrap(), original);
+
+    let gif = blocks::read_file(&output).unwrap();
+    assert_eq!(gif.data.len(), report.stripped_size);
+    assert_eq!(gif.applications().count(), 1);
+    assert_eq!(gif.trailing, None);
+
+    fs::remove_file(input).unwrap();
+    fs::remove_file(output).unwrap();
+}
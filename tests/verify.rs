@@ -0,0 +1,54 @@
+use std::fs;
+
+use gifmeta::gifmeta_structs::{GifError, ModifyOptions};
+use gifmeta::{mod_gif_from_bytes, verify_mod_gif_from_bytes};
+
+const INPUT: &str = "tests/testdata/loop/2.gif";
+
+#[test]
+fn test_mod_with_verify_passes() {
+    let data = fs::read(INPUT).unwrap();
+    let options = ModifyOptions {
+        loop_count: Some(0),
+        delays: Some([(0, 20)].into_iter().collect()),
+        append_comments: vec!["checked".to_string()],
+        xmp: Some("<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>".to_string()),
+        verify: true,
+        ..Default::default()
+    };
+    let (_, report) = mod_gif_from_bytes(&data, &options).unwrap();
+    let verification = report.verification.unwrap();
+    assert!(verification.is_ok());
+    assert_eq!(verification.frames_compared, 2);
+
+    // Verifying on its own is not a modification
+    let only_verify = ModifyOptions {
+        verify: true,
+        ..Default::default()
+    };
+    assert!(matches!(
+        mod_gif_from_bytes(&data, &only_verify),
+        Err(GifError::NoModifications)
+    ));
+}
+
+#[test]
+fn test_verify_reports_unrequested_and_missing_changes() {
+    let data = fs::read(INPUT).unwrap();
+    let options = ModifyOptions {
+        delays: Some([(1, 9)].into_iter().collect()),
+        ..Default::default()
+    };
+    // An output that recolors the palette and never applied the delay
+    let mut output = data.clone();
+    output[13..16].copy_from_slice(&[1, 2, 3]);
+
+    let report = verify_mod_gif_from_bytes(&data, &output, &options).unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.problems[0], "frames[1].delay_cs is 3, expected 9");
+    assert!(report.problems[1].starts_with("global_palette changed: 256 → 256"));
+    // Recoloring changes what every frame renders
+    assert!(report.problems[2..]
+        .iter()
+        .all(|p| p.contains("pixels changed")));
+}
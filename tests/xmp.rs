@@ -0,0 +1,105 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use gifmeta::blocks;
+use gifmeta::get_xmp;
+use gifmeta::gifmeta_structs::ModifyOptions;
+use gifmeta::utils::xmp::{magic_trailer, MAGIC_TRAILER_LEN, XMP_IDENTIFIER};
+
+const XMP: &str = r#"<?xpacket begin="﻿" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description xmlns:dc="http://purl.org/dc/elements/1.1/">
+   <dc:rights>© Sacred Tools — all rights reserved</dc:rights>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+#[test]
+fn test_xmp_is_embedded_with_magic_trailer() {
+    let input = Path::new("tests/testdata/loop/2frame-soft.gif");
+    let output = Path::new("tests/testdata/loop/2frame-soft-xmp.gif");
+
+    common::modify(
+        input,
+        output,
+        ModifyOptions {
+            xmp: Some(XMP.into()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // The generic sub-block walk must land exactly on the block terminator
+    let gif = blocks::read_file(output).unwrap();
+    let app = gif
+        .applications()
+        .find(|a| a.identifier == XMP_IDENTIFIER)
+        .unwrap();
+    let raw = gif.bytes(app.data.span);
+    assert_eq!(raw.len(), XMP.len() + MAGIC_TRAILER_LEN);
+    assert!(raw.ends_with(&magic_trailer()));
+
+    assert_eq!(get_xmp(output).unwrap().as_deref(), Some(XMP));
+    let meta = gifmeta::get_metadata(output, false).unwrap();
+    assert_eq!(meta.xmp_size, Some(XMP.len()));
+    assert_eq!(meta.frame_count, 2);
+
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_xmp_replace_and_strip() {
+    let input = Path::new("tests/testdata/loop/2frame-soft.gif");
+    let with_xmp = Path::new("tests/testdata/loop/2frame-soft-xmp-a.gif");
+    let replaced = Path::new("tests/testdata/loop/2frame-soft-xmp-b.gif");
+    let stripped = Path::new("tests/testdata/loop/2frame-soft-xmp-c.gif");
+
+    common::modify(
+        input,
+        with_xmp,
+        ModifyOptions {
+            xmp: Some(XMP.into()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let other = XMP.replace("all rights reserved", "CC-BY-4.0");
+    common::modify(
+        with_xmp,
+        replaced,
+        ModifyOptions {
+            xmp: Some(other.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(get_xmp(replaced).unwrap(), Some(other));
+    let gif = blocks::read_file(replaced).unwrap();
+    assert_eq!(
+        gif.applications()
+            .filter(|a| a.identifier == XMP_IDENTIFIER)
+            .count(),
+        1
+    );
+
+    common::modify(
+        replaced,
+        stripped,
+        ModifyOptions {
+            strip_xmp: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(get_xmp(stripped).unwrap(), None);
+    assert_eq!(fs::read(stripped).unwrap(), fs::read(input).unwrap());
+
+    for path in [with_xmp, replaced, stripped] {
+        fs::remove_file(path).unwrap();
+    }
+}